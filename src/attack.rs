@@ -1,11 +1,19 @@
 use std::collections::HashSet;
 
 use godot::{
-    classes::{AnimatedSprite2D, Area2D},
+    classes::{AnimatedSprite2D, Area2D, CollisionShape2D, Curve, RandomNumberGenerator, Shape2D},
     prelude::*,
 };
 
-#[derive(GodotConvert, Var, Export, Debug, Clone, Copy)]
+use crate::{
+    components::{is_hostile, Faction, StatusEffectApplication, StatusEffectKind},
+    core::{rotation_from_vector, Facing8},
+    damage::{deliver_hit, receiver_health, DamageType, HitInfo},
+    managers::{game_feel::GameFeel, run_stats::record_player_damage},
+    projectile::Projectile,
+};
+
+#[derive(GodotConvert, Var, Export, Debug, Clone, Copy, PartialEq, Eq)]
 #[godot(via = i32)]
 pub enum CostKind {
     Health,
@@ -23,6 +31,63 @@ impl CostKind {
     }
 }
 
+#[derive(GodotConvert, Var, Export, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[godot(via = i32)]
+pub enum AttackMode {
+    /// Enables the `Hurtbox` for as long as the sprite animation plays.
+    #[default]
+    Melee,
+    /// Spawns `projectile_scene` instances in the direction the attack is facing.
+    Projectile,
+    /// Spawns a `zone_scene` attack into the level, which lingers where it was placed.
+    Zone,
+}
+
+#[derive(GodotConvert, Var, Export, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[godot(via = i32)]
+pub enum AimMode {
+    /// Snaps to the nearest of 8 directions, for sprite-directional swings.
+    #[default]
+    Snapped8,
+    /// Snaps to the nearest of up, down, left and right.
+    Snapped4,
+    /// Points exactly where the player aimed.
+    Continuous,
+}
+
+impl AimMode {
+    /// Rotation in degrees to face along `direction`.
+    pub fn to_rotation(&self, direction: Vector2) -> f32 {
+        match self {
+            AimMode::Snapped8 => Facing8::from_any_vector(direction).to_rotation(),
+            AimMode::Snapped4 => Facing8::from_any_vector_cardinal(direction).to_rotation(),
+            AimMode::Continuous => rotation_from_vector(direction),
+        }
+    }
+}
+
+#[derive(GodotConvert, Var, Export, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[godot(via = i32)]
+pub enum AttackTrigger {
+    /// Fired by the player's attack inputs.
+    #[default]
+    Manual,
+    /// Fires every `auto_interval` seconds at the nearest enemy in range.
+    AutoNearest,
+    /// Fires every `auto_interval` seconds at a random enemy in range.
+    AutoRandom,
+}
+
+#[derive(GodotConvert, Var, Export, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[godot(via = i32)]
+pub enum TargetPriority {
+    /// Hits the bodies closest to the attack first.
+    #[default]
+    Nearest,
+    /// Hits the bodies with the least health first.
+    LowestHealth,
+}
+
 #[derive(GodotClass)]
 #[class(base=Node2D)]
 pub struct Attack {
@@ -33,11 +98,146 @@ pub struct Attack {
     #[export]
     animation_name: StringName,
     #[export]
+    trigger: AttackTrigger,
+    #[export]
+    auto_interval: f64,
+    /// Auto attacks only fire at enemies within this distance.
+    #[export]
+    auto_range: f32,
+    auto_elapsed: f64,
+    #[export]
+    aim_mode: AimMode,
+    #[export]
     damage: i32,
     #[export]
-    cost_kind: CostKind,
+    damage_type: DamageType,
+    /// Damage is randomly scaled by up to plus or minus this fraction.
+    #[export]
+    damage_variance: f32,
+    /// Chance, between 0 and 1, for a hit to crit.
+    #[export]
+    crit_chance: f32,
+    #[export]
+    crit_multiplier: f32,
+    /// Added to `crit_chance`, for player stats to modify at runtime.
+    #[var]
+    bonus_crit_chance: f32,
+    rng: Gd<RandomNumberGenerator>,
+    /// Seconds of hit-stop requested when this attack lands, 0 to disable.
+    #[export]
+    hit_stop_duration: f64,
+    /// Camera shake, in pixels, requested per point of damage dealt.
+    #[export]
+    shake_per_damage: f32,
+    #[export]
+    knockback: f32,
+    #[export]
+    stun_duration: f64,
+    #[export]
+    on_hit_effect: StatusEffectKind,
+    #[export]
+    on_hit_effect_duration: f64,
+    /// Damage per tick for Poison and Burn, or the fraction (0-1) of speed removed for Slow.
+    /// Freeze ignores it. Leave at 0 to use the effect's default.
+    #[export]
+    on_hit_effect_magnitude: f32,
+    /// Most bodies a single use of this attack can hit, 0 for no limit.
+    #[export]
+    max_targets: i32,
+    /// Which bodies to hit first when more enter the hurtbox together than `max_targets` allows.
+    #[export]
+    target_priority: TargetPriority,
+    /// Ends the attack as soon as `max_targets` bodies have been hit.
+    #[export]
+    end_on_max_targets: bool,
+    targets_hit: i32,
+    pending_bodies: Vec<Gd<Node2D>>,
+    /// Seconds between hits on bodies that stay inside the hurtbox, 0 to hit each body once.
+    #[export]
+    tick_interval: f64,
+    tick_elapsed: f64,
+    /// Seconds the attack stays active for, 0 to end with the sprite animation. Use a looping
+    /// animation when this outlasts it.
+    #[export]
+    duration: f64,
+    active_elapsed: f64,
+    /// Whether this attack may interrupt another attack that is still playing.
+    #[export]
+    can_cancel: bool,
+    /// Health spent to start the attack. It is never allowed to kill the wielder.
+    #[export]
+    health_cost: f32,
+    #[export]
+    stamina_cost: f32,
+    #[export]
+    mana_cost: f32,
+    /// Fraction, between 0 and 1, of the costs given back when the attack hits nothing.
+    /// Only melee attacks refund, as projectiles and zones land after the attack has finished.
+    #[export]
+    miss_refund: f32,
+    spent_costs: Vec<(CostKind, f32)>,
+    pending_refund: Vec<(CostKind, f32)>,
+    landed_hit: bool,
+    /// Seconds after starting before the attack can be started again.
+    #[export]
+    cooldown: f64,
+    cooldown_remaining: f64,
+    /// Inclusive `(first, last)` animation frame ranges during which the hurtbox is active.
+    /// Leave empty to keep the hurtbox active for the whole animation.
+    #[export]
+    active_frames: Array<Vector2i>,
+    /// Optional hurtbox shape for each animation frame, indexed by frame.
+    #[export]
+    frame_shapes: Array<Gd<Shape2D>>,
+    /// The hurtbox's own shape, used on frames without an entry in `frame_shapes`.
+    default_shape: Option<Gd<Shape2D>>,
+    /// Held attacks charge up while the input is held and fire on release.
+    #[export]
+    chargeable: bool,
+    /// Seconds of holding needed to reach full charge.
+    #[export]
+    charge_time: f64,
+    /// Held attacks are released automatically after this many seconds.
+    #[export]
+    max_hold_time: f64,
+    /// Maps charge progress (0-1) to how much of the charged scaling applies. Linear when unset.
+    #[export]
+    charge_curve: Option<Gd<Curve>>,
+    /// Damage multiplier at full charge.
+    #[export]
+    charged_damage_scale: f32,
+    /// Node scale at full charge, which also grows the hurtbox.
     #[export]
-    cost_value: f32,
+    charged_size_scale: f32,
+    /// Cost multiplier at full charge.
+    #[export]
+    charged_cost_scale: f32,
+    charge: f32,
+    /// Scale set in the editor, which charging multiplies.
+    authored_scale: Vector2,
+    #[export]
+    mode: AttackMode,
+    #[export]
+    projectile_scene: Option<Gd<PackedScene>>,
+    #[export]
+    projectile_count: i32,
+    #[export]
+    projectile_speed: f32,
+    #[export]
+    projectile_lifetime: f64,
+    /// Number of extra bodies a projectile passes through before it is freed.
+    #[export]
+    projectile_pierce: i32,
+    /// Total arc, in degrees, that multiple projectiles are fanned across.
+    #[export]
+    projectile_spread: f32,
+    /// Attack scene placed into the level in zone mode. It should set its own `duration`.
+    /// Zones without a `Faction` of their own take a copy of the wielder's when spawned.
+    #[export]
+    zone_scene: Option<Gd<PackedScene>>,
+    free_on_finish: bool,
+    /// The attack that spawned this zone, so the zone never hits that attack's wielder either.
+    spawned_by: Option<Gd<Node>>,
     seen_bodies: HashSet<InstanceId>,
 }
 
@@ -49,32 +249,131 @@ impl INode2D for Attack {
             enabled: false,
             name: OnEditor::from_sentinel(StringName::from("Attack")),
             animation_name: StringName::from("default"),
+            trigger: AttackTrigger::default(),
+            auto_interval: 1.0,
+            auto_range: 200.0,
+            auto_elapsed: 0.0,
+            aim_mode: AimMode::default(),
             damage: 50i32,
-            cost_kind: CostKind::Mana,
-            cost_value: 0.0,
+            damage_type: DamageType::default(),
+            damage_variance: 0.0,
+            crit_chance: 0.0,
+            crit_multiplier: 2.0,
+            bonus_crit_chance: 0.0,
+            rng: RandomNumberGenerator::new_gd(),
+            hit_stop_duration: 0.0,
+            shake_per_damage: 0.0,
+            knockback: 0.0,
+            stun_duration: 0.0,
+            on_hit_effect: StatusEffectKind::default(),
+            on_hit_effect_duration: 3.0,
+            on_hit_effect_magnitude: 0.0,
+            max_targets: 0,
+            target_priority: TargetPriority::default(),
+            end_on_max_targets: false,
+            targets_hit: 0,
+            pending_bodies: Vec::new(),
+            tick_interval: 0.0,
+            tick_elapsed: 0.0,
+            duration: 0.0,
+            active_elapsed: 0.0,
+            can_cancel: false,
+            health_cost: 0.0,
+            stamina_cost: 0.0,
+            mana_cost: 0.0,
+            miss_refund: 0.0,
+            spent_costs: Vec::new(),
+            pending_refund: Vec::new(),
+            landed_hit: false,
+            cooldown: 0.0,
+            cooldown_remaining: 0.0,
+            active_frames: Array::new(),
+            frame_shapes: Array::new(),
+            default_shape: None,
+            chargeable: false,
+            charge_time: 1.0,
+            max_hold_time: 3.0,
+            charge_curve: None,
+            charged_damage_scale: 2.0,
+            charged_size_scale: 1.5,
+            charged_cost_scale: 2.0,
+            charge: 0.0,
+            authored_scale: Vector2::ONE,
+            mode: AttackMode::default(),
+            projectile_scene: None,
+            projectile_count: 1,
+            projectile_speed: 300.0,
+            projectile_lifetime: 2.0,
+            projectile_pierce: 0,
+            projectile_spread: 0.0,
+            zone_scene: None,
+            free_on_finish: false,
+            spawned_by: None,
             seen_bodies: HashSet::new(),
         }
     }
 
     fn ready(&mut self) {
         godot_print!("Attack component ready!");
+        self.authored_scale = self.base().get_scale();
 
-        let mut hurtbox: Gd<Area2D> = self.base().get_node_as::<Area2D>("Hurtbox");
+        let mut hurtbox = self.hurtbox();
         let main = self.to_gd();
         hurtbox
             .signals()
             .body_entered()
             .connect_other(&main, Self::on_hurtbox_body_entered);
         hurtbox.set_monitoring(false);
-        let mut sprite: Gd<AnimatedSprite2D> = self
-            .base()
-            .get_node_as::<AnimatedSprite2D>("AnimatedSprite2D");
+        self.default_shape = hurtbox
+            .try_get_node_as::<CollisionShape2D>("CollisionShape2D")
+            .and_then(|collision_shape| collision_shape.get_shape());
+        let mut sprite = self.sprite();
         sprite
             .signals()
             .animation_finished()
             .connect_other(&main, Self::on_sprite_finished);
+        sprite
+            .signals()
+            .frame_changed()
+            .connect_other(&main, Self::update_hit_window);
         sprite.set_visible(false);
     }
+
+    fn process(&mut self, delta: f64) {
+        if self.cooldown_remaining > 0.0 {
+            self.cooldown_remaining -= delta;
+            if self.cooldown_remaining <= 0.0 {
+                self.cooldown_remaining = 0.0;
+                self.signals().cooldown_ready().emit();
+            }
+        }
+        if self.is_auto() {
+            self.auto_elapsed += delta;
+        }
+    }
+
+    fn physics_process(&mut self, delta: f64) {
+        if self.enabled && self.duration > 0.0 {
+            self.active_elapsed += delta;
+            if self.active_elapsed >= self.duration {
+                self.cancel();
+                return;
+            }
+        }
+        if self.enabled && self.tick_interval > 0.0 {
+            self.tick_elapsed += delta;
+            if self.tick_elapsed >= self.tick_interval {
+                self.tick_elapsed -= self.tick_interval;
+                self.queue_tick();
+            }
+        }
+        if !self.pending_bodies.is_empty() {
+            self.resolve_pending_hits();
+            if self.end_on_max_targets && self.reached_max_targets() {
+                self.cancel();
+            }
+        }
+    }
 }
 
 #[godot_api]
@@ -85,27 +384,201 @@ impl Attack {
     #[signal]
     pub fn attack_finished();
 
-    pub fn start(&mut self) {
+    #[signal]
+    pub fn cooldown_ready();
+
+    /// True while the attack animation is playing.
+    #[func]
+    pub fn is_busy(&self) -> bool {
+        self.enabled
+    }
+
+    #[func]
+    pub fn get_cooldown_remaining(&self) -> f64 {
+        self.cooldown_remaining
+    }
+
+    #[func]
+    pub fn can_start(&self) -> bool {
+        !self.enabled && self.cooldown_remaining <= 0.0
+    }
+
+    /// Starts the attack, returning false if it is busy or still cooling down.
+    pub fn start(&mut self) -> bool {
+        if !self.can_start() {
+            return false;
+        }
         self.enabled = true;
+        self.cooldown_remaining = self.cooldown;
+        self.auto_elapsed = 0.0;
         self.seen_bodies.clear();
+        self.pending_bodies.clear();
+        self.targets_hit = 0;
+        self.tick_elapsed = 0.0;
+        self.active_elapsed = 0.0;
+        self.landed_hit = false;
 
-        // Enable hurtbox
-        let mut hurtbox: Gd<Area2D> = self.base().get_node_as::<Area2D>("Hurtbox");
-        hurtbox.set_monitoring(true);
+        let size = self.charge_scale(self.charged_size_scale);
+        let scale = self.authored_scale * size;
+        self.base_mut().set_scale(scale);
+
+        match self.mode {
+            AttackMode::Melee => {}
+            AttackMode::Projectile => self.spawn_projectiles(),
+            AttackMode::Zone => self.spawn_zone(),
+        }
 
         // Show and start sprite animation
-        let mut sprite: Gd<AnimatedSprite2D> = self
-            .base()
-            .get_node_as::<AnimatedSprite2D>("AnimatedSprite2D");
+        let mut sprite = self.sprite();
         sprite.set_visible(true);
         sprite.play_ex().name(&self.animation_name).done();
+
+        // Enable hurtbox if the first frame is active
+        self.update_hit_window();
+        true
+    }
+
+    pub fn get_trigger(&self) -> AttackTrigger {
+        self.trigger
     }
 
-    pub fn get_cost(&self) -> (CostKind, f32) {
-        (self.cost_kind, self.cost_value)
+    pub fn is_auto(&self) -> bool {
+        self.trigger != AttackTrigger::Manual
     }
 
-    fn on_hurtbox_body_entered(&mut self, mut body: Gd<Node2D>) {
+    /// True once an auto attack's timer has elapsed and it's able to start.
+    pub fn is_auto_fire_due(&self) -> bool {
+        self.is_auto() && self.auto_elapsed >= self.auto_interval && self.can_start()
+    }
+
+    pub fn get_auto_range(&self) -> f32 {
+        self.auto_range
+    }
+
+    /// Rotation in degrees the attack should face to aim along `direction`.
+    pub fn aim_rotation(&self, direction: Vector2) -> f32 {
+        self.aim_mode.to_rotation(direction)
+    }
+
+    /// Seeds the RNG used for damage variance and crits, for reproducible rolls.
+    #[func]
+    pub fn set_rng_seed(&mut self, seed: i64) {
+        self.rng.set_seed(seed as u64);
+    }
+
+    pub fn get_attack_name(&self) -> StringName {
+        (*self.name).clone()
+    }
+
+    /// Every resource the attack costs at `charge` progress (0-1), skipping free ones.
+    pub fn get_costs(&self, charge: f32) -> Vec<(CostKind, f32)> {
+        let scale = self.charge_scale_at(self.charged_cost_scale, charge);
+        [
+            (CostKind::Health, self.health_cost),
+            (CostKind::Stamina, self.stamina_cost),
+            (CostKind::Mana, self.mana_cost),
+        ]
+        .into_iter()
+        .filter(|(_, value)| *value > 0.0)
+        .map(|(kind, value)| (kind, value * scale))
+        .collect()
+    }
+
+    /// Records what was paid to start the attack, so a whiff can refund part of it.
+    pub fn set_spent_costs(&mut self, costs: Vec<(CostKind, f32)>) {
+        self.spent_costs = costs;
+    }
+
+    /// Takes the refund owed by the last attack if it missed, to be given back by the caller.
+    pub fn take_refund(&mut self) -> Vec<(CostKind, f32)> {
+        std::mem::take(&mut self.pending_refund)
+    }
+
+    pub fn is_chargeable(&self) -> bool {
+        self.chargeable
+    }
+
+    /// Charge progress (0-1) after holding the input for `held` seconds.
+    pub fn charge_progress(&self, held: f64) -> f32 {
+        if self.charge_time <= 0.0 {
+            1.0
+        } else {
+            (held / self.charge_time).clamp(0.0, 1.0) as f32
+        }
+    }
+
+    pub fn should_release(&self, held: f64) -> bool {
+        held >= self.max_hold_time
+    }
+
+    /// Sets the charge progress (0-1) used by the next `start()` and the hits it deals.
+    pub fn set_charge(&mut self, progress: f32) {
+        self.charge = progress.clamp(0.0, 1.0);
+    }
+
+    /// Damages `body` with this attack, returning whether the body could receive the hit.
+    ///
+    /// Shared by the melee hurtbox and any projectiles this attack spawned.
+    pub fn apply_hit(&mut self, body: Gd<Node2D>, direction: Vector2) -> bool {
+        // Never hit whoever is wielding this attack.
+        let wielded: Gd<Node> = self.to_gd().upcast();
+        if body.is_ancestor_of(&wielded) {
+            return false;
+        }
+        // Nor, for zones, whoever wields the attack that spawned them.
+        if let Some(spawner) = self
+            .spawned_by
+            .as_ref()
+            .filter(|spawner| spawner.is_instance_valid())
+        {
+            if body.is_ancestor_of(spawner) {
+                return false;
+            }
+        }
+        // Nor anyone on a team the wielder's faction isn't hostile to.
+        if !is_hostile(&wielded, &body.clone().upcast()) {
+            return false;
+        }
+
+        // Emit hit body signal
+        self.signals().hit_body().emit(&body.clone());
+
+        let (damage, crit) = self.roll_damage();
+        let hit = HitInfo {
+            source: Some(self.to_gd().upcast()),
+            attack_name: (*self.name).clone(),
+            damage,
+            damage_type: self.damage_type,
+            direction,
+            crit,
+            knockback: self.knockback,
+            stun_duration: self.stun_duration,
+            status_effect: (self.on_hit_effect != StatusEffectKind::None).then_some(
+                StatusEffectApplication {
+                    kind: self.on_hit_effect,
+                    duration: self.on_hit_effect_duration,
+                    magnitude: if self.on_hit_effect_magnitude > 0.0 {
+                        self.on_hit_effect_magnitude
+                    } else {
+                        self.on_hit_effect.default_magnitude()
+                    },
+                    source: Some(self.to_gd().upcast()),
+                },
+            ),
+            is_tick: false,
+        };
+        let Some(applied) = deliver_hit(body, &hit) else {
+            return false;
+        };
+        self.landed_hit = true;
+        self.request_game_feel(hit.damage);
+        if let Some(tree) = self.base().get_tree() {
+            record_player_damage(&tree, &wielded, applied);
+        }
+        true
+    }
+
+    fn on_hurtbox_body_entered(&mut self, body: Gd<Node2D>) {
         if !self.enabled || self.seen_bodies.contains(&body.instance_id()) {
             return;
         }
@@ -113,29 +586,288 @@ impl Attack {
         // Add body to seen bodies
         self.seen_bodies.insert(body.instance_id());
 
-        // Emit hit body signal
-        self.signals().hit_body().emit(&body.clone());
+        // Bodies entering on the same frame are hit together in `physics_process`, so they can
+        // be prioritised against `max_targets`.
+        self.pending_bodies.push(body);
+    }
+
+    fn resolve_pending_hits(&mut self) {
+        let mut bodies = std::mem::take(&mut self.pending_bodies);
+        if !self.enabled {
+            return;
+        }
 
-        // Call hit method on the body if it has one
-        if body.has_method("take_damage") {
-            body.call("take_damage", &[Variant::from(self.damage)]); // Example damage value
+        let origin = self.base().get_global_position();
+        match self.target_priority {
+            TargetPriority::Nearest => bodies.sort_by(|a, b| {
+                let a = a.get_global_position().distance_squared_to(origin);
+                let b = b.get_global_position().distance_squared_to(origin);
+                a.total_cmp(&b)
+            }),
+            TargetPriority::LowestHealth => bodies.sort_by(|a, b| {
+                let a = receiver_health(a).unwrap_or(f64::MAX);
+                let b = receiver_health(b).unwrap_or(f64::MAX);
+                a.total_cmp(&b)
+            }),
+        }
+
+        for body in bodies {
+            if self.reached_max_targets() {
+                break;
+            }
+            let direction = (body.get_global_position() - origin).normalized_or_zero();
+            if self.apply_hit(body, direction) {
+                self.targets_hit += 1;
+            }
+        }
+    }
+
+    /// Queues every body still inside the hurtbox to be hit again.
+    fn queue_tick(&mut self) {
+        let hurtbox = self.hurtbox();
+        if !hurtbox.is_monitoring() {
+            return;
+        }
+        // Each tick is a fresh use of the attack as far as `max_targets` is concerned.
+        self.seen_bodies.clear();
+        self.targets_hit = 0;
+        for body in hurtbox.get_overlapping_bodies().iter_shared() {
+            if self.seen_bodies.insert(body.instance_id()) {
+                self.pending_bodies.push(body);
+            }
+        }
+    }
+
+    fn reached_max_targets(&self) -> bool {
+        self.max_targets > 0 && self.targets_hit >= self.max_targets
+    }
+
+    /// Enables the hurtbox only while the sprite is on one of the `active_frames`.
+    fn update_hit_window(&mut self) {
+        if !self.enabled || self.mode != AttackMode::Melee {
+            return;
+        }
+        let frame = self.sprite().get_frame();
+        let mut hurtbox = self.hurtbox();
+        hurtbox.set_monitoring(self.is_frame_active(frame));
+
+        if !self.frame_shapes.is_empty() {
+            let shape = self
+                .frame_shapes
+                .get(frame as usize)
+                .or_else(|| self.default_shape.clone());
+            self.set_hurtbox_shape(shape);
+        }
+    }
+
+    fn set_hurtbox_shape(&self, shape: Option<Gd<Shape2D>>) {
+        match self
+            .hurtbox()
+            .try_get_node_as::<CollisionShape2D>("CollisionShape2D")
+        {
+            Some(mut collision_shape) => collision_shape.set_shape(shape.as_ref()),
+            None => godot_print!("Hurtbox has no CollisionShape2D to swap shapes on!"),
+        }
+    }
+
+    fn is_frame_active(&self, frame: i32) -> bool {
+        self.active_frames.is_empty()
+            || self
+                .active_frames
+                .iter_shared()
+                .any(|range| (range.x..=range.y).contains(&frame))
+    }
+
+    pub fn can_cancel(&self) -> bool {
+        self.can_cancel
+    }
+
+    /// Ends the attack early, as if its animation had finished.
+    pub fn cancel(&mut self) {
+        if self.enabled {
+            self.sprite().stop();
+            self.finish();
         }
     }
 
     fn on_sprite_finished(&mut self) {
+        // Attacks with a duration outlast their animation and end in `physics_process`.
+        if self.duration <= 0.0 {
+            self.finish();
+        }
+    }
+
+    fn finish(&mut self) {
+        // Bodies that entered since the last physics tick still count as hit.
+        if !self.pending_bodies.is_empty() {
+            self.resolve_pending_hits();
+        }
         self.enabled = false;
 
         // Disable hurtbox
-        let mut hurtbox: Gd<Area2D> = self.base().get_node_as::<Area2D>("Hurtbox");
-        hurtbox.set_monitoring(false);
+        self.hurtbox().set_monitoring(false);
+        if !self.frame_shapes.is_empty() {
+            self.set_hurtbox_shape(self.default_shape.clone());
+        }
 
         // Hide sprite
-        let mut sprite: Gd<AnimatedSprite2D> = self
-            .base()
-            .get_node_as::<AnimatedSprite2D>("AnimatedSprite2D");
-        sprite.set_visible(false);
+        self.sprite().set_visible(false);
+
+        let spent = std::mem::take(&mut self.spent_costs);
+        if self.mode == AttackMode::Melee && !self.landed_hit && self.miss_refund > 0.0 {
+            let fraction = self.miss_refund.clamp(0.0, 1.0);
+            self.pending_refund = spent
+                .into_iter()
+                .map(|(kind, value)| (kind, value * fraction))
+                .collect();
+        }
 
         // Emit attack finished signal
         self.signals().attack_finished().emit();
+
+        if self.free_on_finish {
+            self.base_mut().queue_free();
+        }
+    }
+}
+
+impl Attack {
+    fn request_game_feel(&self, damage: i64) {
+        if self.hit_stop_duration <= 0.0 && self.shake_per_damage <= 0.0 {
+            return;
+        }
+        let game_feel = self
+            .base()
+            .get_tree()
+            .and_then(|tree| GameFeel::find(&tree));
+        if let Some(mut game_feel) = game_feel {
+            let mut game_feel = game_feel.bind_mut();
+            game_feel.request_hit_stop(self.hit_stop_duration);
+            game_feel.request_shake(damage as f32 * self.shake_per_damage);
+        }
+    }
+
+    /// Rolls the damage for a single hit, returning it along with whether it crit.
+    fn roll_damage(&mut self) -> (i64, bool) {
+        let mut damage = self.damage as f32 * self.charge_scale(self.charged_damage_scale);
+        if self.damage_variance > 0.0 {
+            let variance = self.damage_variance;
+            damage *= 1.0 + self.rng.randf_range(-variance, variance);
+        }
+        let crit = self.rng.randf() < self.crit_chance + self.bonus_crit_chance;
+        if crit {
+            damage *= self.crit_multiplier;
+        }
+        (damage.round().max(0.0) as i64, crit)
+    }
+
+    /// Interpolates from 1 to `full_scale` along the charge curve.
+    fn charge_scale(&self, full_scale: f32) -> f32 {
+        self.charge_scale_at(full_scale, self.charge)
+    }
+
+    fn charge_scale_at(&self, full_scale: f32, charge: f32) -> f32 {
+        // The curve may not start at 0, so attacks that can't charge must skip it entirely.
+        if !self.chargeable {
+            return 1.0;
+        }
+        let weight = match &self.charge_curve {
+            Some(curve) => curve.sample(charge),
+            None => charge,
+        };
+        1.0 + (full_scale - 1.0) * weight
+    }
+
+    fn hurtbox(&self) -> Gd<Area2D> {
+        self.base().get_node_as::<Area2D>("Hurtbox")
+    }
+
+    fn sprite(&self) -> Gd<AnimatedSprite2D> {
+        self.base()
+            .get_node_as::<AnimatedSprite2D>("AnimatedSprite2D")
+    }
+
+    fn spawn_projectiles(&mut self) {
+        let Some(scene) = self.projectile_scene.clone() else {
+            godot_print!("Attack '{}' has no projectile scene!", *self.name);
+            return;
+        };
+        // Projectiles are added to the level so they don't follow the player around.
+        let Some(mut container) = self
+            .base()
+            .get_tree()
+            .and_then(|tree| tree.get_current_scene())
+        else {
+            godot_print!("No current scene to spawn projectiles into!");
+            return;
+        };
+
+        let origin = self.base().get_global_position();
+        let rotation = self.base().get_global_rotation();
+        let count = self.projectile_count.max(1);
+        let spread = self.projectile_spread.to_radians();
+        let source = self.to_gd();
+        for i in 0..count {
+            let offset = if count > 1 {
+                spread * (i as f32 / (count - 1) as f32 - 0.5)
+            } else {
+                0.0
+            };
+            let Some(mut projectile) = scene.try_instantiate_as::<Projectile>() else {
+                godot_print!("Failed to instantiate projectile scene!");
+                return;
+            };
+            projectile.bind_mut().launch(
+                source.clone(),
+                // Attack rotation is measured from up, see `Facing8::to_rotation`.
+                Vector2::UP.rotated(rotation + offset),
+                self.projectile_speed,
+                self.projectile_lifetime,
+                self.projectile_pierce,
+            );
+            container.add_child(&projectile);
+            projectile.set_global_position(origin);
+            projectile.set_global_rotation(rotation + offset);
+        }
+    }
+
+    fn spawn_zone(&mut self) {
+        let Some(scene) = self.zone_scene.clone() else {
+            godot_print!("Attack '{}' has no zone scene!", *self.name);
+            return;
+        };
+        // Zones are added to the level so they stay where they were placed.
+        let Some(mut container) = self
+            .base()
+            .get_tree()
+            .and_then(|tree| tree.get_current_scene())
+        else {
+            godot_print!("No current scene to spawn zones into!");
+            return;
+        };
+        let Some(mut zone) = scene.try_instantiate_as::<Attack>() else {
+            godot_print!("Failed to instantiate zone scene!");
+            return;
+        };
+
+        // Zones outlive their place under the wielder, and possibly this attack, so they keep
+        // their own copy of the wielder's faction.
+        if zone.try_get_node_as::<Faction>("Faction").is_none() {
+            let copy = Faction::of(&self.to_gd().upcast()).and_then(|faction| faction.duplicate());
+            if let Some(mut copy) = copy {
+                copy.set_name("Faction");
+                zone.add_child(&copy);
+            }
+        }
+        {
+            let mut zone = zone.bind_mut();
+            zone.free_on_finish = true;
+            zone.spawned_by = Some(self.to_gd().upcast());
+            zone.charge = self.charge;
+        }
+        container.add_child(&zone);
+        zone.set_global_position(self.base().get_global_position());
+        zone.set_global_rotation(self.base().get_global_rotation());
+        zone.bind_mut().start();
     }
 }
@@ -0,0 +1,35 @@
+use godot::prelude::*;
+
+/// A sequence of attack inputs that fires a finisher `Attack` when completed.
+#[derive(GodotClass)]
+#[class(base=Resource)]
+pub struct ComboDefinition {
+    base: Base<Resource>,
+    /// Attack inputs in order, by slot name, e.g. `["Main", "Main", "Secondary"]`.
+    #[export]
+    sequence: PackedStringArray,
+    /// `name` of the `Attack` fired in place of the final input's attack.
+    #[export]
+    finisher: StringName,
+}
+
+#[godot_api]
+impl IResource for ComboDefinition {
+    fn init(base: Base<Resource>) -> Self {
+        Self {
+            base,
+            sequence: PackedStringArray::new(),
+            finisher: StringName::default(),
+        }
+    }
+}
+
+impl ComboDefinition {
+    pub fn get_sequence(&self) -> &PackedStringArray {
+        &self.sequence
+    }
+
+    pub fn get_finisher(&self) -> &StringName {
+        &self.finisher
+    }
+}
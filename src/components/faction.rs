@@ -0,0 +1,96 @@
+use godot::prelude::*;
+
+#[derive(GodotConvert, Var, Export, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[godot(via = i32)]
+pub enum Team {
+    /// Bodies without a `Faction` are treated as neutral.
+    #[default]
+    Neutral,
+    Player,
+    Enemy,
+}
+
+const ALL_TEAMS: i32 = 0b111;
+
+impl Team {
+    /// This team's bit in `Faction.hostile_to`.
+    fn bit(&self) -> i32 {
+        1 << (*self as i32)
+    }
+}
+
+/// Which team a node is on and which teams its attacks may hit.
+///
+/// Attacks and bodies find their faction in a child named `Faction` on themselves or their
+/// closest ancestor that has one, so an attack under the player uses the player's faction.
+/// Every player, enemy, pet and tower scene needs one: attackers without a faction hit
+/// everything, and targets without one are treated as neutral.
+#[derive(GodotClass)]
+#[class(base=Node2D)]
+pub struct Faction {
+    base: Base<Node2D>,
+    #[export]
+    team: Team,
+    /// Ignores `hostile_to` and makes this faction hostile to every team but its own.
+    #[export]
+    use_default_hostility: bool,
+    /// Teams this faction's attacks may hit, when `use_default_hostility` is off.
+    #[export(flags = (Neutral = 1, Player = 2, Enemy = 4))]
+    hostile_to: i32,
+}
+
+#[godot_api]
+impl INode2D for Faction {
+    fn init(base: Base<Node2D>) -> Self {
+        Self {
+            base,
+            team: Team::default(),
+            use_default_hostility: true,
+            hostile_to: 0,
+        }
+    }
+
+    fn ready(&mut self) {
+        godot_print!("Faction component ready!");
+        if self.use_default_hostility {
+            self.hostile_to = ALL_TEAMS & !self.team.bit();
+        }
+    }
+}
+
+#[godot_api]
+impl Faction {
+    #[func]
+    pub fn is_hostile_to(&self, team: Team) -> bool {
+        self.hostile_to & team.bit() != 0
+    }
+}
+
+impl Faction {
+    /// The faction `node` belongs to, if it or any of its ancestors has one.
+    pub fn of(node: &Gd<Node>) -> Option<Gd<Faction>> {
+        let mut current = Some(node.clone());
+        while let Some(node) = current {
+            if let Some(faction) = node.try_get_node_as::<Faction>("Faction") {
+                return Some(faction);
+            }
+            current = node.get_parent();
+        }
+        None
+    }
+}
+
+/// The team `node` is on, if it or any of its ancestors has a faction.
+pub fn team_of(node: &Gd<Node>) -> Option<Team> {
+    Faction::of(node).map(|faction| faction.bind().team)
+}
+
+/// Whether `attacker` may hit `target`. Attackers without a faction hit everything, so
+/// scenes that predate factions keep working until they are given one.
+pub fn is_hostile(attacker: &Gd<Node>, target: &Gd<Node>) -> bool {
+    let Some(attacker) = Faction::of(attacker) else {
+        return true;
+    };
+    let target_team = team_of(target).unwrap_or(Team::Neutral);
+    attacker.bind().is_hostile_to(target_team)
+}
@@ -46,6 +46,10 @@ impl GenericAttribute {
         self.value = (self.value + delta).clamp(0.0, self.max_value);
     }
 
+    pub fn can_take(&self, amount: f64) -> bool {
+        amount <= self.value
+    }
+
     pub fn take(&mut self, amount: f64) -> bool {
         if amount > self.value {
             false
@@ -1,5 +1,10 @@
 use godot::{classes::AnimationPlayer, prelude::*};
 
+use crate::{
+    damage::{DamageType, HitInfo},
+    managers::damage_number_manager::{DamageNumberManager, DamageNumberStyle},
+};
+
 #[derive(GodotClass)]
 #[class(base=Node2D)]
 pub struct Health {
@@ -8,6 +13,16 @@ pub struct Health {
     target: OnEditor<Gd<Node2D>>,
     #[export]
     health: i64,
+    /// Healing never raises health above this. Raised to the starting health if lower.
+    #[export]
+    max_health: i64,
+    /// Incoming damage multipliers keyed by `DamageType` name, e.g. `{"Spectral": 2.0}`.
+    /// Missing types take full damage and a multiplier of 0 makes the target immune.
+    #[export]
+    damage_multipliers: Dictionary,
+    /// Hurt flash colour for critical hits, normal hits flash red.
+    #[export]
+    crit_color: Color,
     animation_player: OnReady<Gd<AnimationPlayer>>,
 }
 
@@ -18,12 +33,16 @@ impl INode2D for Health {
             base,
             target: OnEditor::default(),
             health: 100,
+            max_health: 100,
+            damage_multipliers: Dictionary::new(),
+            crit_color: Color::from_rgb(1.0, 0.85, 0.0),
             animation_player: OnReady::from_node("AnimationPlayer"),
         }
     }
 
     fn ready(&mut self) {
         godot_print!("Health component ready!");
+        self.max_health = self.max_health.max(self.health);
         let stop_effect = self.base().callable("stop_effect");
         self.animation_player
             .connect("animation_finished", &stop_effect);
@@ -44,24 +63,85 @@ impl Health {
 
     #[signal]
     pub fn died();
+
+    /// Heals up to `max_health` and returns how much was actually healed.
+    #[func]
+    pub fn heal(&mut self, amount: i64) -> i64 {
+        let healed = amount.clamp(0, (self.max_health - self.health).max(0));
+        if healed > 0 {
+            self.health += healed;
+            self.show_damage_number(healed, DamageNumberStyle::Heal);
+        }
+        healed
+    }
 }
 
 impl Health {
-    pub fn take_damage(&mut self, damage: i64) -> bool {
+    pub fn current_health(&self) -> i64 {
+        self.health
+    }
+
+    pub fn damage_multiplier(&self, damage_type: DamageType) -> f64 {
+        self.damage_multipliers
+            .get(GString::from(damage_type.to_name()))
+            .and_then(|multiplier| {
+                // Designers may type whole numbers into the inspector.
+                multiplier
+                    .try_to::<f64>()
+                    .or_else(|_| multiplier.try_to::<i64>().map(|m| m as f64))
+                    .ok()
+            })
+            .unwrap_or(1.0)
+    }
+
+    /// Applies `hit` after resistances, returning the damage taken and whether it was fatal.
+    pub fn take_damage(&mut self, hit: &HitInfo) -> (i64, bool) {
+        let multiplier = self.damage_multiplier(hit.damage_type);
+        let style = if hit.crit {
+            DamageNumberStyle::Crit
+        } else if multiplier < 1.0 {
+            DamageNumberStyle::Resisted
+        } else {
+            DamageNumberStyle::Normal
+        };
+        if multiplier <= 0.0 {
+            // Immune, so skip the hurt effect entirely.
+            self.show_damage_number(0, style);
+            return (0, false);
+        }
+        let damage = (hit.damage as f64 * multiplier).round() as i64;
         self.health -= damage;
+        self.show_damage_number(damage, style);
         self.animation_player.set_current_animation("hurt");
         self.animation_player.play();
 
-        self.target.set_modulate(Color::RED);
+        let color = if hit.crit {
+            self.crit_color
+        } else {
+            Color::RED
+        };
+        self.target.set_modulate(color);
 
         if self.health <= 0 {
             // Emits this signal, but actually using it in the parent results in re-entrancy issues
             // so try to use the returned dead bool instead
             // self.base_mut().emit_signal("died", &[]);
             self.signals().died().emit();
-            true
+            (damage, true)
         } else {
-            false
+            (damage, false)
+        }
+    }
+
+    fn show_damage_number(&self, amount: i64, style: DamageNumberStyle) {
+        let numbers = self
+            .base()
+            .get_tree()
+            .and_then(|tree| DamageNumberManager::find(&tree));
+        if let Some(mut numbers) = numbers {
+            numbers
+                .bind_mut()
+                .spawn(self.target.get_global_position(), amount, style);
         }
     }
 }
@@ -1,3 +1,7 @@
+mod faction;
 pub mod generic_attribute;
 mod health;
+mod status_effects;
+pub use faction::{is_hostile, team_of, Faction, Team};
 pub use health::Health;
+pub use status_effects::{StatusEffectApplication, StatusEffectKind, StatusEffects};
@@ -0,0 +1,344 @@
+use godot::prelude::*;
+
+use crate::{
+    damage::{deliver_hit, DamageType, HitInfo},
+    managers::run_stats::record_player_damage,
+};
+
+#[derive(GodotConvert, Var, Export, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[godot(via = i32)]
+pub enum StatusEffectKind {
+    #[default]
+    None,
+    /// Deals `magnitude` damage per tick for each stack.
+    Poison,
+    /// Deals `magnitude` damage per tick.
+    Burn,
+    /// Reduces movement speed by `magnitude`, a fraction between 0 and 1.
+    Slow,
+    /// Stops movement entirely, ignoring `magnitude`.
+    Freeze,
+}
+
+impl StatusEffectKind {
+    pub fn to_name(&self) -> &str {
+        match self {
+            StatusEffectKind::None => "None",
+            StatusEffectKind::Poison => "Poison",
+            StatusEffectKind::Burn => "Burn",
+            StatusEffectKind::Slow => "Slow",
+            StatusEffectKind::Freeze => "Freeze",
+        }
+    }
+
+    /// Magnitude used when an attack doesn't set one, in the units described on each kind.
+    pub fn default_magnitude(&self) -> f32 {
+        match self {
+            StatusEffectKind::Poison | StatusEffectKind::Burn => 1.0,
+            StatusEffectKind::Slow => 0.3,
+            StatusEffectKind::None | StatusEffectKind::Freeze => 0.0,
+        }
+    }
+
+    fn stacking(&self) -> Stacking {
+        match self {
+            StatusEffectKind::Poison => Stacking::Intensify,
+            StatusEffectKind::None
+            | StatusEffectKind::Burn
+            | StatusEffectKind::Slow
+            | StatusEffectKind::Freeze => Stacking::Refresh,
+        }
+    }
+
+    fn tick_damage_type(&self) -> Option<DamageType> {
+        match self {
+            StatusEffectKind::Poison => Some(DamageType::Poison),
+            StatusEffectKind::Burn => Some(DamageType::Fire),
+            StatusEffectKind::None | StatusEffectKind::Slow | StatusEffectKind::Freeze => None,
+        }
+    }
+}
+
+/// How re-applying an effect combines with one that is already active.
+enum Stacking {
+    /// Keep a single stack, taking the longer duration and stronger magnitude.
+    Refresh,
+    /// Add a stack, up to `StatusEffects.max_stacks`, and refresh the duration.
+    Intensify,
+}
+
+/// An effect carried by a hit, to be applied by the receiver's `StatusEffects`.
+#[derive(Debug, Clone)]
+pub struct StatusEffectApplication {
+    pub kind: StatusEffectKind,
+    pub duration: f64,
+    pub magnitude: f32,
+    /// The node that applied the effect, credited with its damage ticks.
+    pub source: Option<Gd<Node2D>>,
+}
+
+struct ActiveEffect {
+    kind: StatusEffectKind,
+    remaining: f64,
+    magnitude: f32,
+    stacks: i32,
+    tick_elapsed: f64,
+    source: Option<Gd<Node2D>>,
+}
+
+#[derive(GodotClass)]
+#[class(base=Node2D)]
+pub struct StatusEffects {
+    base: Base<Node2D>,
+    target: OnReady<Gd<Node2D>>,
+    /// Seconds between damage ticks of damage-over-time effects.
+    #[export]
+    tick_interval: f64,
+    #[export]
+    max_stacks: i32,
+    effects: Vec<ActiveEffect>,
+}
+
+#[godot_api]
+impl INode2D for StatusEffects {
+    fn init(base: Base<Node2D>) -> Self {
+        Self {
+            base,
+            target: OnReady::from_base_fn(|base| base.get_parent().unwrap().cast::<Node2D>()),
+            tick_interval: 1.0,
+            max_stacks: 5,
+            effects: Vec::new(),
+        }
+    }
+
+    fn ready(&mut self) {
+        godot_print!("StatusEffects component ready!");
+    }
+
+    fn process(&mut self, delta: f64) {
+        let mut ticks = Vec::new();
+        for effect in self.effects.iter_mut() {
+            effect.remaining -= delta;
+            if let Some(damage_type) = effect.kind.tick_damage_type() {
+                effect.tick_elapsed += delta;
+                if self.tick_interval > 0.0 && effect.tick_elapsed >= self.tick_interval {
+                    effect.tick_elapsed -= self.tick_interval;
+                    let damage = (effect.magnitude * effect.stacks as f32).round() as i64;
+                    // The source may have been freed since, e.g. a zone that has ended.
+                    let source = effect.source.clone().filter(|s| s.is_instance_valid());
+                    ticks.push((effect.kind, damage_type, damage, source));
+                }
+            }
+        }
+
+        let mut expired = Vec::new();
+        self.effects.retain(|effect| {
+            if effect.remaining > 0.0 {
+                true
+            } else {
+                expired.push(effect.kind);
+                false
+            }
+        });
+
+        for (kind, damage_type, damage, source) in ticks {
+            let hit = HitInfo {
+                source: source.clone(),
+                attack_name: StringName::from(kind.to_name()),
+                damage,
+                damage_type,
+                direction: Vector2::ZERO,
+                crit: false,
+                knockback: 0.0,
+                stun_duration: 0.0,
+                status_effect: None,
+                is_tick: true,
+            };
+            let applied = deliver_hit((*self.target).clone(), &hit);
+            if let (Some(applied), Some(source), Some(tree)) =
+                (applied, source, self.base().get_tree())
+            {
+                record_player_damage(&tree, &source.upcast(), applied);
+            }
+        }
+
+        for kind in expired {
+            self.signals().effect_expired().emit(kind);
+        }
+    }
+}
+
+#[godot_api]
+impl StatusEffects {
+    #[signal]
+    pub fn effect_applied(effect: StatusEffectKind, stacks: i32);
+
+    #[signal]
+    pub fn effect_expired(effect: StatusEffectKind);
+
+    #[func]
+    pub fn has_effect(&self, kind: StatusEffectKind) -> bool {
+        self.effects.iter().any(|effect| effect.kind == kind)
+    }
+
+    /// Multiplier to apply to the target's movement speed.
+    #[func]
+    pub fn speed_multiplier(&self) -> f32 {
+        self.effects
+            .iter()
+            .map(|effect| match effect.kind {
+                StatusEffectKind::Freeze => 0.0,
+                StatusEffectKind::Slow => (1.0 - effect.magnitude).clamp(0.0, 1.0),
+                _ => 1.0,
+            })
+            .product()
+    }
+}
+
+impl StatusEffects {
+    pub fn apply(&mut self, application: &StatusEffectApplication) {
+        let kind = application.kind;
+        if kind == StatusEffectKind::None {
+            return;
+        }
+
+        let stacks = stack_effect(&mut self.effects, application, self.max_stacks);
+
+        self.signals().effect_applied().emit(kind, stacks);
+    }
+}
+
+/// Adds `application` to `effects`, returning how many stacks of its kind are now active.
+fn stack_effect(
+    effects: &mut Vec<ActiveEffect>,
+    application: &StatusEffectApplication,
+    max_stacks: i32,
+) -> i32 {
+    let kind = application.kind;
+    match effects.iter_mut().find(|effect| effect.kind == kind) {
+        Some(effect) => {
+            effect.remaining = effect.remaining.max(application.duration);
+            effect.source = application.source.clone();
+            match kind.stacking() {
+                Stacking::Refresh => {
+                    effect.magnitude = effect.magnitude.max(application.magnitude);
+                }
+                Stacking::Intensify => {
+                    effect.stacks = (effect.stacks + 1).min(max_stacks.max(1));
+                }
+            }
+            effect.stacks
+        }
+        None => {
+            effects.push(ActiveEffect {
+                kind,
+                remaining: application.duration,
+                magnitude: application.magnitude,
+                stacks: 1,
+                tick_elapsed: 0.0,
+                source: application.source.clone(),
+            });
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn application(
+        kind: StatusEffectKind,
+        duration: f64,
+        magnitude: f32,
+    ) -> StatusEffectApplication {
+        StatusEffectApplication {
+            kind,
+            duration,
+            magnitude,
+            source: None,
+        }
+    }
+
+    #[test]
+    fn new_effect_starts_with_one_stack() {
+        let mut effects = Vec::new();
+        let stacks = stack_effect(
+            &mut effects,
+            &application(StatusEffectKind::Burn, 2.0, 3.0),
+            5,
+        );
+
+        assert_eq!(stacks, 1);
+        assert_eq!(effects.len(), 1);
+        assert_eq!(effects[0].remaining, 2.0);
+        assert_eq!(effects[0].magnitude, 3.0);
+    }
+
+    #[test]
+    fn refresh_keeps_one_stack_with_longest_duration_and_strongest_magnitude() {
+        let mut effects = Vec::new();
+        stack_effect(
+            &mut effects,
+            &application(StatusEffectKind::Slow, 3.0, 0.2),
+            5,
+        );
+        let stacks = stack_effect(
+            &mut effects,
+            &application(StatusEffectKind::Slow, 1.0, 0.5),
+            5,
+        );
+
+        assert_eq!(stacks, 1);
+        assert_eq!(effects.len(), 1);
+        assert_eq!(effects[0].remaining, 3.0);
+        assert_eq!(effects[0].magnitude, 0.5);
+    }
+
+    #[test]
+    fn intensify_adds_stacks_up_to_the_limit() {
+        let mut effects = Vec::new();
+        let poison = application(StatusEffectKind::Poison, 2.0, 1.0);
+        let stacks: Vec<i32> = (0..4)
+            .map(|_| stack_effect(&mut effects, &poison, 3))
+            .collect();
+
+        assert_eq!(stacks, vec![1, 2, 3, 3]);
+        assert_eq!(effects.len(), 1);
+    }
+
+    #[test]
+    fn intensify_refreshes_duration() {
+        let mut effects = Vec::new();
+        stack_effect(
+            &mut effects,
+            &application(StatusEffectKind::Poison, 1.0, 1.0),
+            5,
+        );
+        effects[0].remaining = 0.25;
+        stack_effect(
+            &mut effects,
+            &application(StatusEffectKind::Poison, 1.0, 1.0),
+            5,
+        );
+
+        assert_eq!(effects[0].remaining, 1.0);
+    }
+
+    #[test]
+    fn different_kinds_are_tracked_separately() {
+        let mut effects = Vec::new();
+        stack_effect(
+            &mut effects,
+            &application(StatusEffectKind::Burn, 1.0, 1.0),
+            5,
+        );
+        stack_effect(
+            &mut effects,
+            &application(StatusEffectKind::Freeze, 1.0, 0.0),
+            5,
+        );
+
+        assert_eq!(effects.len(), 2);
+    }
+}
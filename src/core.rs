@@ -50,6 +50,34 @@ impl Facing8 {
         }
     }
 
+    /// Like `from_any_vector`, but only snaps to Up, Down, Left or Right.
+    pub fn from_any_vector_cardinal(vector: Vector2) -> Self {
+        if vector.x.abs() > vector.y.abs() {
+            if vector.x > 0.0 {
+                Facing8::Right
+            } else {
+                Facing8::Left
+            }
+        } else if vector.y < 0.0 {
+            Facing8::Up
+        } else {
+            Facing8::Down
+        }
+    }
+
+    pub fn to_vector(&self) -> Vector2 {
+        match self {
+            Facing8::Up => Vector2::UP,
+            Facing8::Down => Vector2::DOWN,
+            Facing8::Left => Vector2::LEFT,
+            Facing8::Right => Vector2::RIGHT,
+            Facing8::UpLeft => Vector2::new(-1.0, -1.0).normalized(),
+            Facing8::UpRight => Vector2::new(1.0, -1.0).normalized(),
+            Facing8::DownLeft => Vector2::new(-1.0, 1.0).normalized(),
+            Facing8::DownRight => Vector2::new(1.0, 1.0).normalized(),
+        }
+    }
+
     pub fn to_rotation(&self) -> f32 {
         match self {
             Facing8::Up => 0.0,
@@ -63,3 +91,103 @@ impl Facing8 {
         }
     }
 }
+
+/// Rotation in degrees, matching `Facing8::to_rotation`, that points exactly along `vector`.
+pub fn rotation_from_vector(vector: Vector2) -> f32 {
+    // `Vector2::angle` is measured from the right, whereas our rotations are measured from up.
+    vector.angle().to_degrees() + 90.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Facing8; 8] = [
+        Facing8::Up,
+        Facing8::Left,
+        Facing8::Right,
+        Facing8::Down,
+        Facing8::UpLeft,
+        Facing8::UpRight,
+        Facing8::DownLeft,
+        Facing8::DownRight,
+    ];
+
+    /// Whether two rotations in degrees point the same way.
+    fn same_rotation(a: f32, b: f32) -> bool {
+        let difference = (a - b).rem_euclid(360.0);
+        difference < 1e-3 || difference > 360.0 - 1e-3
+    }
+
+    #[test]
+    fn cardinal_snaps_axis_vectors() {
+        assert!(matches!(
+            Facing8::from_any_vector_cardinal(Vector2::new(0.0, -3.0)),
+            Facing8::Up
+        ));
+        assert!(matches!(
+            Facing8::from_any_vector_cardinal(Vector2::new(0.0, 3.0)),
+            Facing8::Down
+        ));
+        assert!(matches!(
+            Facing8::from_any_vector_cardinal(Vector2::new(-3.0, 0.0)),
+            Facing8::Left
+        ));
+        assert!(matches!(
+            Facing8::from_any_vector_cardinal(Vector2::new(3.0, 0.0)),
+            Facing8::Right
+        ));
+        assert!(matches!(
+            Facing8::from_any_vector_cardinal(Vector2::new(2.0, -1.0)),
+            Facing8::Right
+        ));
+    }
+
+    #[test]
+    fn cardinal_breaks_diagonal_ties_vertically() {
+        assert!(matches!(
+            Facing8::from_any_vector_cardinal(Vector2::new(1.0, 1.0)),
+            Facing8::Down
+        ));
+        assert!(matches!(
+            Facing8::from_any_vector_cardinal(Vector2::new(-1.0, 1.0)),
+            Facing8::Down
+        ));
+        assert!(matches!(
+            Facing8::from_any_vector_cardinal(Vector2::new(1.0, -1.0)),
+            Facing8::Up
+        ));
+        assert!(matches!(
+            Facing8::from_any_vector_cardinal(Vector2::new(-1.0, -1.0)),
+            Facing8::Up
+        ));
+        assert!(matches!(
+            Facing8::from_any_vector_cardinal(Vector2::ZERO),
+            Facing8::Down
+        ));
+    }
+
+    #[test]
+    fn to_vector_is_a_unit_vector_facing_back() {
+        for facing in ALL {
+            let vector = facing.to_vector();
+            assert!((vector.length() - 1.0).abs() < 1e-5, "{facing:?}");
+            assert_eq!(
+                format!("{:?}", Facing8::from_any_vector(vector)),
+                format!("{facing:?}")
+            );
+        }
+    }
+
+    #[test]
+    fn rotation_from_vector_matches_to_rotation() {
+        for facing in ALL {
+            let rotation = rotation_from_vector(facing.to_vector());
+            assert!(
+                same_rotation(rotation, facing.to_rotation()),
+                "{facing:?}: {rotation} != {}",
+                facing.to_rotation()
+            );
+        }
+    }
+}
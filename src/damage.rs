@@ -0,0 +1,79 @@
+use godot::prelude::*;
+
+use crate::components::StatusEffectApplication;
+
+#[derive(GodotConvert, Var, Export, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[godot(via = i32)]
+pub enum DamageType {
+    #[default]
+    Physical,
+    Fire,
+    Frost,
+    Poison,
+    Spectral,
+}
+
+impl DamageType {
+    /// Name used as the key in `Health.damage_multipliers`.
+    pub fn to_name(&self) -> &str {
+        match self {
+            DamageType::Physical => "Physical",
+            DamageType::Fire => "Fire",
+            DamageType::Frost => "Frost",
+            DamageType::Poison => "Poison",
+            DamageType::Spectral => "Spectral",
+        }
+    }
+}
+
+/// Everything a receiver needs to know about a single hit.
+#[derive(Debug, Clone)]
+pub struct HitInfo {
+    /// The node that dealt the hit, usually an `Attack`.
+    pub source: Option<Gd<Node2D>>,
+    pub attack_name: StringName,
+    pub damage: i64,
+    pub damage_type: DamageType,
+    /// Normalised direction the hit travelled in.
+    pub direction: Vector2,
+    pub crit: bool,
+    /// Speed, in pixels per second, the receiver is pushed along `direction`.
+    pub knockback: f32,
+    /// Seconds the receiver should stop acting for.
+    pub stun_duration: f64,
+    pub status_effect: Option<StatusEffectApplication>,
+    /// Damage-over-time tick from a status effect, which ignores and doesn't grant i-frames.
+    pub is_tick: bool,
+}
+
+/// Implemented by anything that attacks can damage.
+///
+/// Implementations must be registered with `#[godot_dyn]` so [`deliver_hit`] can find them.
+pub trait HitReceiver {
+    /// Takes `hit`, returning the damage actually applied after resistances, i-frames and
+    /// anything else that reduced or ignored it.
+    fn receive_hit(&mut self, hit: &HitInfo) -> i64;
+
+    fn current_health(&self) -> f64;
+}
+
+/// Delivers `hit` to `body`, returning the damage applied, or `None` if the body can't
+/// receive hits.
+pub fn deliver_hit(body: Gd<Node2D>, hit: &HitInfo) -> Option<i64> {
+    body.try_dynify::<dyn HitReceiver>()
+        .ok()
+        .map(|mut receiver| receiver.dyn_bind_mut().receive_hit(hit))
+}
+
+/// Whether `body` can receive hits, as opposed to walls and other level geometry.
+pub fn is_hit_receiver(body: &Gd<Node2D>) -> bool {
+    body.clone().try_dynify::<dyn HitReceiver>().is_ok()
+}
+
+/// Current health of `body`, or `None` if it can't receive hits.
+pub fn receiver_health(body: &Gd<Node2D>) -> Option<f64> {
+    body.clone()
+        .try_dynify::<dyn HitReceiver>()
+        .ok()
+        .map(|receiver| receiver.dyn_bind().current_health())
+}
@@ -1,9 +1,16 @@
 use godot::classes::{
-    AnimatedSprite2D, AnimationTree, CollisionShape2D, IStaticBody2D, StaticBody2D, Timer,
+    AnimatedSprite2D, AnimationTree, CollisionShape2D, IStaticBody2D, SceneTree, StaticBody2D,
+    Timer,
 };
 use godot::prelude::*;
 
-use crate::components::Health;
+use crate::attack::Attack;
+use crate::components::{is_hostile, team_of, Health, StatusEffects, Team};
+use crate::damage::{deliver_hit, DamageType, HitInfo, HitReceiver};
+use crate::managers::run_stats::RunStats;
+
+/// Scene tree group every enemy adds itself to.
+pub const ENEMY_GROUP: &str = "enemies";
 
 #[derive(GodotClass)]
 #[class(base=StaticBody2D)]
@@ -12,8 +19,29 @@ pub struct Enemy {
     #[export]
     action_state: ActionState,
     health: OnReady<Gd<Health>>,
+    status_effects: Option<Gd<StatusEffects>>,
     #[export]
     speed: f32,
+    /// How quickly knockback velocity decays, in pixels per second squared.
+    #[export]
+    knockback_friction: f32,
+    knockback_velocity: Vector2,
+    stun_remaining: f64,
+    /// Damage dealt to the player while touching them, 0 to disable.
+    #[export]
+    contact_damage: i32,
+    #[export]
+    contact_damage_type: DamageType,
+    /// Seconds between contact damage ticks.
+    #[export]
+    contact_interval: f64,
+    contact_cooldown: f64,
+    /// Whether the last move bumped into the player.
+    touching_player: bool,
+    /// Distance to the player at which the optional `Attack` child is started.
+    #[export]
+    attack_range: f32,
+    attack: Option<Gd<Attack>>,
     sprite: OnReady<Gd<AnimatedSprite2D>>,
     animation_tree: OnReady<Gd<AnimationTree>>,
     nav_agent: OnReady<Gd<Node>>,
@@ -28,7 +56,18 @@ impl IStaticBody2D for Enemy {
             base,
             sprite: OnReady::from_node("AnimatedSprite2D"),
             health: OnReady::from_node("Health"),
+            status_effects: None,
             speed: 10.0,
+            knockback_friction: 600.0,
+            knockback_velocity: Vector2::ZERO,
+            stun_remaining: 0.0,
+            contact_damage: 0,
+            contact_damage_type: DamageType::default(),
+            contact_interval: 1.0,
+            contact_cooldown: 0.0,
+            touching_player: false,
+            attack_range: 32.0,
+            attack: None,
             action_state: ActionState::default(),
             animation_tree: OnReady::from_node("AnimationTree"),
             nav_agent: OnReady::from_node("NavigationAgent2D"),
@@ -38,53 +77,153 @@ impl IStaticBody2D for Enemy {
     }
 
     fn ready(&mut self) {
+        self.base_mut().add_to_group(ENEMY_GROUP);
+        self.status_effects = self
+            .base()
+            .try_get_node_as::<StatusEffects>("StatusEffects");
+        self.attack = self.base().try_get_node_as::<Attack>("Attack");
         // let die = self.base().callable("die");
         // self.health.connect("died", &die);
     }
 
     fn physics_process(&mut self, delta: f64) {
         if self.alive {
-            let target = self.player.get_global_position();
-            self.nav_agent
-                .set("target_position", &Variant::from(target));
-            let target = self
-                .nav_agent
-                .call("get_next_path_position", &[])
-                .to::<Vector2>();
-
-            let direction = (target - self.base().get_global_position()).normalized_or_zero();
-            // godot_print!("Direction: {:?}", direction);
-
-            let speed = self.speed;
-            let mut base = self.base_mut();
-            base.move_and_collide(direction * (delta as f32) * speed);
+            let mut velocity = self.knockback_velocity;
+            if self.stun_remaining > 0.0 {
+                self.stun_remaining -= delta;
+            } else {
+                let target = self.player.get_global_position();
+                self.nav_agent
+                    .set("target_position", &Variant::from(target));
+                let target = self
+                    .nav_agent
+                    .call("get_next_path_position", &[])
+                    .to::<Vector2>();
+
+                let direction = (target - self.base().get_global_position()).normalized_or_zero();
+                // godot_print!("Direction: {:?}", direction);
+                let speed_multiplier = self.status_effects.as_ref().map_or(1.0, |status_effects| {
+                    status_effects.bind().speed_multiplier()
+                });
+                velocity += direction * self.speed * speed_multiplier;
+
+                self.attack_player(delta);
+            }
+
+            let delta = delta as f32;
+            self.knockback_velocity = self
+                .knockback_velocity
+                .move_toward(Vector2::ZERO, self.knockback_friction * delta);
+
+            let collision = self.base_mut().move_and_collide(velocity * delta);
+            let player_id = self.player.instance_id();
+            self.touching_player = collision
+                .and_then(|collision| collision.get_collider())
+                .is_some_and(|collider| collider.instance_id() == player_id);
         }
     }
 }
 
-#[godot_api]
-impl Enemy {
-    #[func]
-    pub fn take_damage(&mut self, damage: i64) {
-        let died = {
+#[godot_dyn]
+impl HitReceiver for Enemy {
+    fn receive_hit(&mut self, hit: &HitInfo) -> i64 {
+        if !self.alive {
+            return 0;
+        }
+        self.knockback_velocity += hit.direction * hit.knockback;
+        self.stun_remaining = self.stun_remaining.max(hit.stun_duration);
+        if let (Some(effect), Some(status_effects)) = (&hit.status_effect, &mut self.status_effects)
+        {
+            status_effects.bind_mut().apply(effect);
+        }
+
+        let (damage, died) = {
             let mut health = self.health.bind_mut();
-            health.take_damage(damage)
+            health.take_damage(hit)
         };
         if died {
-            self.die();
+            self.die(hit);
         }
+        damage
+    }
+
+    fn current_health(&self) -> f64 {
+        self.health.bind().current_health() as f64
     }
 }
 
 impl Enemy {
-    fn die(&mut self) {
+    pub fn is_alive(&self) -> bool {
+        self.alive
+    }
+
+    /// Starts the melee attack if the player is in range, or deals contact damage for enemies
+    /// without one.
+    fn attack_player(&mut self, delta: f64) {
+        self.contact_cooldown = (self.contact_cooldown - delta).max(0.0);
+        if !self.player.bind().is_alive() {
+            return;
+        }
+
+        let player = (*self.player).clone().upcast::<Node2D>();
+        let offset = player.get_global_position() - self.base().get_global_position();
+        let distance = offset.length();
+        let direction = offset.normalized_or_zero();
+
+        // Enemies with a melee attack only ever use that, even while it's busy or cooling down.
+        if let Some(attack) = self.attack.as_mut() {
+            if distance <= self.attack_range && attack.bind().can_start() {
+                let rotation = attack.bind().aim_rotation(direction);
+                attack.set_global_rotation_degrees(rotation);
+                attack.bind_mut().start();
+            }
+            return;
+        }
+
+        if self.contact_damage <= 0
+            || !self.touching_player
+            || self.contact_cooldown > 0.0
+            || !is_hostile(&self.to_gd().upcast(), &player.clone().upcast())
+        {
+            return;
+        }
+        self.contact_cooldown = self.contact_interval;
+        let hit = HitInfo {
+            source: Some(self.to_gd().upcast()),
+            attack_name: StringName::from("Contact"),
+            damage: self.contact_damage as i64,
+            damage_type: self.contact_damage_type,
+            direction,
+            crit: false,
+            knockback: 0.0,
+            stun_duration: 0.0,
+            status_effect: None,
+            is_tick: false,
+        };
+        deliver_hit(player, &hit);
+    }
+
+    fn die(&mut self, hit: &HitInfo) {
         self.alive = false;
         self.action_state = ActionState::Dead;
         self.animation_tree.set(
             "parameters/StateMachine/conditions/death",
             &Variant::from(true),
         );
-        godot_print!("Enemy died!");
+        godot_print!("Enemy killed by '{}'!", hit.attack_name);
+        // Only the player's kills count towards the run, not enemies hitting each other.
+        let by_player = hit
+            .source
+            .as_ref()
+            .and_then(|source| team_of(&source.clone().upcast()))
+            == Some(Team::Player);
+        let stats = self
+            .base()
+            .get_tree()
+            .and_then(|tree| RunStats::find(&tree));
+        if let Some(mut stats) = stats.filter(|_| by_player) {
+            stats.bind_mut().record_kill();
+        }
         self.base()
             .get_node_as::<CollisionShape2D>("CollisionShape2D")
             .call_deferred("set_disabled", &[Variant::from(true)]);
@@ -123,3 +262,11 @@ pub enum ActionState {
     Walking,
     Dead,
 }
+
+pub fn living_enemies(tree: &Gd<SceneTree>) -> Vec<Gd<Enemy>> {
+    tree.get_nodes_in_group(ENEMY_GROUP)
+        .iter_shared()
+        .filter_map(|node| node.try_cast::<Enemy>().ok())
+        .filter(|enemy| enemy.bind().is_alive())
+        .collect()
+}
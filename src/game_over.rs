@@ -0,0 +1,89 @@
+use godot::{
+    classes::{Button, CanvasLayer, ICanvasLayer, Label},
+    prelude::*,
+};
+
+use crate::{managers::run_stats::RunStats, player::Player};
+
+/// Results screen shown when the player dies, offering to restart or quit to the menu.
+#[derive(GodotClass)]
+#[class(base=CanvasLayer)]
+pub struct GameOver {
+    base: Base<CanvasLayer>,
+    #[export]
+    player: OnEditor<Gd<Player>>,
+    #[export]
+    stats_label: OnEditor<Gd<Label>>,
+    #[export]
+    restart_button: OnEditor<Gd<Button>>,
+    #[export]
+    menu_button: OnEditor<Gd<Button>>,
+    #[export(file = "*.tscn")]
+    menu_scene: GString,
+}
+
+#[godot_api]
+impl ICanvasLayer for GameOver {
+    fn init(base: Base<CanvasLayer>) -> Self {
+        Self {
+            base,
+            player: OnEditor::default(),
+            stats_label: OnEditor::default(),
+            restart_button: OnEditor::default(),
+            menu_button: OnEditor::default(),
+            menu_scene: GString::new(),
+        }
+    }
+
+    fn ready(&mut self) {
+        godot_print!("Game over screen ready.");
+        self.base_mut().set_visible(false);
+
+        let main = self.to_gd();
+        self.player
+            .signals()
+            .player_died()
+            .connect_other(&main, Self::on_player_died);
+        self.restart_button
+            .signals()
+            .pressed()
+            .connect_other(&main, Self::on_restart_pressed);
+        self.menu_button
+            .signals()
+            .pressed()
+            .connect_other(&main, Self::on_menu_pressed);
+    }
+}
+
+#[godot_api]
+impl GameOver {
+    fn on_player_died(&mut self) {
+        let summary = self
+            .base()
+            .get_tree()
+            .and_then(|tree| RunStats::find(&tree))
+            .map(|stats| stats.bind().summary());
+        match summary {
+            Some(summary) => self.stats_label.set_text(summary.as_str()),
+            None => godot_print!("No run stats found for the game over screen!"),
+        }
+        self.base_mut().set_visible(true);
+        self.restart_button.grab_focus();
+    }
+
+    fn on_restart_pressed(&mut self) {
+        if let Some(mut tree) = self.base().get_tree() {
+            tree.reload_current_scene();
+        }
+    }
+
+    fn on_menu_pressed(&mut self) {
+        if self.menu_scene.is_empty() {
+            godot_print!("No menu scene set on the game over screen!");
+            return;
+        }
+        if let Some(mut tree) = self.base().get_tree() {
+            tree.change_scene_to_file(&self.menu_scene);
+        }
+    }
+}
@@ -3,12 +3,16 @@ use godot::prelude::*;
 struct PurranormalDefence;
 
 mod attack;
+mod combo;
 mod components;
 mod core;
+mod damage;
 mod enemy;
+mod game_over;
 mod hud;
 mod managers;
 mod player;
+mod projectile;
 
 #[gdextension]
 unsafe impl ExtensionLibrary for PurranormalDefence {}
@@ -1,11 +1,20 @@
-use godot::{classes::Input, prelude::*};
+use std::{collections::HashMap, str::FromStr};
+
+use godot::{
+    classes::{
+        Input, InputEvent, InputEventJoypadButton, InputEventJoypadMotion, InputEventMouse,
+        RandomNumberGenerator,
+    },
+    prelude::*,
+};
 use strum::IntoEnumIterator;
-use strum_macros::EnumIter;
+use strum_macros::{EnumIter, EnumString};
 
 use crate::{
-    attack::{Attack, CostKind},
+    attack::{Attack, AttackTrigger, CostKind},
+    combo::ComboDefinition,
     components::generic_attribute::GenericAttribute,
-    core::Facing8,
+    enemy::living_enemies,
     player::Player,
 };
 
@@ -14,7 +23,45 @@ use crate::{
 pub struct AttackManager {
     base: Base<Node2D>,
     attacks: Vec<Gd<Attack>>,
+    loadout: HashMap<AttackType, Gd<Attack>>,
+    // Initial loadout, slots can be changed at runtime with `equip`, `unequip` and `swap`.
+    #[export]
+    main_attack: Option<Gd<Attack>>,
+    #[export]
+    secondary_attack: Option<Gd<Attack>>,
+    #[export]
+    slot_1_attack: Option<Gd<Attack>>,
+    #[export]
+    slot_2_attack: Option<Gd<Attack>>,
+    #[export]
+    slot_3_attack: Option<Gd<Attack>>,
+    #[export]
+    slot_4_attack: Option<Gd<Attack>>,
     player: OnReady<Gd<Player>>,
+    charging: Option<ChargeState>,
+    #[export]
+    combos: Array<Gd<ComboDefinition>>,
+    /// Seconds allowed after an attack finishes before a combo resets.
+    #[export]
+    combo_window: f64,
+    combo_sequences: Vec<(Vec<AttackType>, StringName)>,
+    combo_history: Vec<AttackType>,
+    combo_timer: f64,
+    /// Seconds an input is held while another attack plays before it is dropped.
+    #[export]
+    input_buffer_window: f64,
+    queued_attack: Option<QueuedAttack>,
+    aim_device: AimDevice,
+    /// With a neutral right stick, aim at the nearest enemy in front of the player.
+    #[export]
+    auto_aim: bool,
+    #[export]
+    auto_aim_range: f32,
+    /// Width, in degrees, of the cone in front of the player that auto-aim searches.
+    #[export]
+    auto_aim_cone: f32,
+    /// Picks targets for `AutoRandom` attacks.
+    rng: Gd<RandomNumberGenerator>,
 }
 
 #[godot_api]
@@ -23,7 +70,27 @@ impl INode2D for AttackManager {
         Self {
             base,
             attacks: Vec::new(),
+            loadout: HashMap::new(),
+            main_attack: None,
+            secondary_attack: None,
+            slot_1_attack: None,
+            slot_2_attack: None,
+            slot_3_attack: None,
+            slot_4_attack: None,
             player: OnReady::from_base_fn(|base| base.get_parent().unwrap().cast::<Player>()),
+            charging: None,
+            combos: Array::new(),
+            combo_window: 0.5,
+            combo_sequences: Vec::new(),
+            combo_history: Vec::new(),
+            combo_timer: 0.0,
+            input_buffer_window: 0.3,
+            queued_attack: None,
+            aim_device: AimDevice::default(),
+            auto_aim: true,
+            auto_aim_range: 200.0,
+            auto_aim_cone: 90.0,
+            rng: RandomNumberGenerator::new_gd(),
         }
     }
 
@@ -36,75 +103,577 @@ impl INode2D for AttackManager {
             // TODO jheiser: Cast to some Attack type? once one exists.
             .filter_map(|child| child.try_cast::<Attack>().ok())
             .collect();
+        self.init_loadout();
+        self.combo_sequences = self.parse_combos();
+
+        let main = self.to_gd();
+        self.player
+            .signals()
+            .took_damage()
+            .connect_other(&main, Self::on_player_took_damage);
+        self.player
+            .signals()
+            .player_died()
+            .connect_other(&main, Self::on_player_died);
+    }
+
+    fn input(&mut self, event: Gd<InputEvent>) {
+        // Aim with whichever device was used last.
+        // InputEventMouse covers both mouse motion and mouse buttons.
+        if event.clone().try_cast::<InputEventMouse>().is_ok() {
+            self.aim_device = AimDevice::Mouse;
+        } else if event.clone().try_cast::<InputEventJoypadButton>().is_ok() {
+            self.aim_device = AimDevice::Gamepad;
+        } else if let Ok(motion) = event.try_cast::<InputEventJoypadMotion>() {
+            // Ignore stick drift below the deadzone.
+            if motion.get_axis_value().abs() > GAMEPAD_DEVICE_DEADZONE {
+                self.aim_device = AimDevice::Gamepad;
+            }
+        }
     }
 
-    fn process(&mut self, _delta: f64) {
-        if let Some(attack_input) = self.handle_input() {
+    fn process(&mut self, delta: f64) {
+        // The window runs between attacks, so long combo steps don't time out mid-swing.
+        if !self.combo_history.is_empty() && self.active_attack().is_none() {
+            self.combo_timer += delta;
+            if self.combo_timer > self.combo_window {
+                self.reset_combo();
+            }
+        }
+
+        if let Some(attack_input) = self.handle_input(delta) {
             godot_print!("Attack input: {:?}", attack_input);
-            self.handle_attack(attack_input);
+            self.queue_attack(attack_input);
+        } else if let Some(queued) = self.queued_attack.as_mut() {
+            queued.age += delta;
+            if queued.age > self.input_buffer_window {
+                godot_print!("Dropping buffered attack input: {:?}", queued.input);
+                self.queued_attack = None;
+            }
         }
+
+        self.dispatch_queued_attack();
+        self.fire_auto_attacks();
+        self.collect_refunds();
     }
 }
 
+#[godot_api]
 impl AttackManager {
-    fn handle_input(&self) -> Option<AttackInput> {
+    #[signal]
+    pub fn loadout_changed(slot: AttackType);
+
+    #[func]
+    pub fn get_equipped(&self, slot: AttackType) -> Option<Gd<Attack>> {
+        self.get_attack(slot)
+    }
+
+    /// Instantiates `attack_scene` without binding it to a slot, e.g. for auto-firing weapons.
+    #[func]
+    pub fn collect(&mut self, attack_scene: Gd<PackedScene>) -> bool {
+        let Some(attack) = attack_scene.try_instantiate_as::<Attack>() else {
+            godot_print!("Failed to instantiate attack scene!");
+            return false;
+        };
+        self.base_mut().add_child(&attack);
+        self.attacks.push(attack);
+        true
+    }
+
+    /// Instantiates `attack_scene` into `slot`, freeing whatever was equipped there.
+    #[func]
+    pub fn equip(&mut self, slot: AttackType, attack_scene: Gd<PackedScene>) -> bool {
+        let Some(attack) = attack_scene.try_instantiate_as::<Attack>() else {
+            godot_print!("Failed to instantiate attack scene!");
+            return false;
+        };
+        self.base_mut().add_child(&attack);
+        self.attacks.push(attack.clone());
+        self.set_slot(slot, Some(attack));
+        true
+    }
+
+    /// Frees the attack in `slot`, returning false if the slot was already empty.
+    #[func]
+    pub fn unequip(&mut self, slot: AttackType) -> bool {
+        let equipped = self.loadout.contains_key(&slot);
+        self.set_slot(slot, None);
+        equipped
+    }
+
+    #[func]
+    pub fn swap(&mut self, first: AttackType, second: AttackType) {
+        let first_attack = self.loadout.remove(&first);
+        let second_attack = self.loadout.remove(&second);
+        if let Some(attack) = second_attack {
+            self.loadout.insert(first, attack);
+        }
+        if let Some(attack) = first_attack {
+            self.loadout.insert(second, attack);
+        }
+        self.on_loadout_changed(first);
+        self.on_loadout_changed(second);
+    }
+
+    /// Whether the attack in `slot` could be paid for right now, without spending anything.
+    #[func]
+    pub fn can_afford_slot(&self, slot: AttackType) -> bool {
+        self.get_attack(slot)
+            .is_some_and(|attack| self.can_afford(&attack.bind().get_costs(0.0)))
+    }
+
+    /// Seeds the RNG used to pick targets for random auto attacks, for reproducible runs.
+    #[func]
+    pub fn set_rng_seed(&mut self, seed: i64) {
+        self.rng.set_seed(seed as u64);
+    }
+
+    /// Charge progress (0-1) of the attack currently being held, or 0 if none is.
+    #[func]
+    pub fn get_charge_progress(&self) -> f32 {
+        self.charging
+            .as_ref()
+            .and_then(|charging| {
+                self.get_attack(charging.attack)
+                    .map(|attack| attack.bind().charge_progress(charging.held))
+            })
+            .unwrap_or(0.0)
+    }
+}
+
+impl AttackManager {
+    fn handle_input(&mut self, delta: f64) -> Option<AttackInput> {
         let input = Input::singleton();
+
+        // While charging, the held attack fires on release and other inputs are ignored.
+        if let Some(charging) = self.charging.as_mut() {
+            charging.held += delta;
+            let (attack, held) = (charging.attack, charging.held);
+            let Some(attack_node) = self.get_attack(attack) else {
+                self.charging = None;
+                return None;
+            };
+            let (released, charge) = {
+                let attack_node = attack_node.bind();
+                (
+                    !input.is_action_pressed(attack.to_input_name())
+                        || attack_node.should_release(held),
+                    attack_node.charge_progress(held),
+                )
+            };
+            if !released {
+                return None;
+            }
+            self.charging = None;
+            return Some(AttackInput {
+                attack,
+                aim: self.aim_direction(),
+                charge,
+            });
+        }
+
         for attack_type in AttackType::iter() {
             let input_name = attack_type.to_input_name();
             if input.is_action_just_pressed(input_name) {
-                let attack_direction = Facing8::from_any_vector(
-                    (self.base().get_global_mouse_position() - self.base().get_global_position())
-                        .normalized_or_zero(),
-                );
+                let chargeable = self
+                    .get_attack(attack_type)
+                    .is_some_and(|attack| attack.bind().is_chargeable());
+                if chargeable {
+                    self.charging = Some(ChargeState {
+                        attack: attack_type,
+                        held: 0.0,
+                    });
+                    return None;
+                }
                 return Some(AttackInput {
                     attack: attack_type,
-                    attack_direction,
+                    aim: self.aim_direction(),
+                    charge: 0.0,
                 });
             }
         }
         None
     }
 
-    fn can_afford_attack(&self, cost_kind: CostKind, cost_value: f32) -> bool {
-        if cost_value == 0.0 {
-            return true;
+    fn aim_direction(&self) -> Vector2 {
+        match self.aim_device {
+            AimDevice::Mouse => {
+                let offset =
+                    self.base().get_global_mouse_position() - self.base().get_global_position();
+                offset.normalized_or_zero()
+            }
+            AimDevice::Gamepad => {
+                let stick =
+                    Input::singleton().get_vector("aim_left", "aim_right", "aim_up", "aim_down");
+                if stick != Vector2::ZERO {
+                    return stick.normalized();
+                }
+                let facing = self.player.bind().get_facing().to_vector();
+                if self.auto_aim {
+                    self.auto_aim_target(facing).unwrap_or(facing)
+                } else {
+                    facing
+                }
+            }
+        }
+    }
+
+    /// Direction to the nearest living enemy within the auto-aim cone around `facing`.
+    fn auto_aim_target(&self, facing: Vector2) -> Option<Vector2> {
+        let tree = self.base().get_tree()?;
+        let origin = self.base().get_global_position();
+        let half_cone = (self.auto_aim_cone / 2.0).to_radians();
+        living_enemies(&tree)
+            .into_iter()
+            .map(|enemy| enemy.get_global_position() - origin)
+            .filter(|offset| {
+                offset.length() <= self.auto_aim_range
+                    && facing.angle_to(*offset).abs() <= half_cone
+            })
+            .min_by(|a, b| a.length().total_cmp(&b.length()))
+            .map(|offset| offset.normalized_or_zero())
+    }
+
+    fn get_attack(&self, attack_type: AttackType) -> Option<Gd<Attack>> {
+        self.loadout
+            .get(&attack_type)
+            // Gd<Node2D> is a ref counted pointer, so we can clone it for ~free
+            .cloned()
+    }
+
+    fn exported_attack(&self, slot: AttackType) -> Option<Gd<Attack>> {
+        match slot {
+            AttackType::Main => self.main_attack.clone(),
+            AttackType::Secondary => self.secondary_attack.clone(),
+            AttackType::Slot1 => self.slot_1_attack.clone(),
+            AttackType::Slot2 => self.slot_2_attack.clone(),
+            AttackType::Slot3 => self.slot_3_attack.clone(),
+            AttackType::Slot4 => self.slot_4_attack.clone(),
+        }
+    }
+
+    fn init_loadout(&mut self) {
+        self.loadout = AttackType::iter()
+            .filter_map(|slot| self.exported_attack(slot).map(|attack| (slot, attack)))
+            .collect();
+        for slot in AttackType::iter() {
+            if !self.loadout.contains_key(&slot) {
+                godot_print!("Attack slot {:?} is empty.", slot);
+            }
+        }
+    }
+
+    fn set_slot(&mut self, slot: AttackType, attack: Option<Gd<Attack>>) {
+        let previous = match attack {
+            Some(attack) => self.loadout.insert(slot, attack),
+            None => self.loadout.remove(&slot),
+        };
+        if let Some(mut previous) = previous {
+            self.attacks.retain(|attack| *attack != previous);
+            self.base_mut().remove_child(&previous);
+            previous.queue_free();
         }
+        self.on_loadout_changed(slot);
+    }
+
+    fn on_loadout_changed(&mut self, slot: AttackType) {
+        // Anything pending may refer to an attack that has moved or been freed.
+        self.queued_attack = None;
+        self.charging = None;
+        if !self.loadout.contains_key(&slot) {
+            godot_print!("Attack slot {:?} is empty.", slot);
+        }
+        self.signals().loadout_changed().emit(slot);
+    }
+
+    fn cost_attribute(&self, cost_kind: CostKind) -> Option<Gd<GenericAttribute>> {
         let attribute = self
             .player
             .find_child(cost_kind.to_attribute_name())
             .and_then(|n| n.try_cast::<GenericAttribute>().ok());
-
-        if let Some(mut attr) = attribute {
-            attr.bind_mut().take(cost_value as f64)
-        } else {
+        if attribute.is_none() {
             godot_print!(
                 "Attribute '{}' not found for cost kind {:?}",
                 cost_kind.to_attribute_name(),
                 cost_kind
             );
-            false
+        }
+        attribute
+    }
+
+    /// Checks every cost can be paid without spending anything.
+    fn can_afford(&self, costs: &[(CostKind, f32)]) -> bool {
+        costs.iter().all(|&(cost_kind, cost_value)| {
+            let Some(attribute) = self.cost_attribute(cost_kind) else {
+                return false;
+            };
+            let attribute = attribute.bind();
+            match cost_kind {
+                // Paying with health must leave the player alive.
+                CostKind::Health => attribute.get_value() > cost_value as f64,
+                CostKind::Stamina | CostKind::Mana => attribute.can_take(cost_value as f64),
+            }
+        })
+    }
+
+    /// Spends costs already checked with `can_afford`.
+    fn spend(&self, costs: &[(CostKind, f32)]) {
+        for &(cost_kind, cost_value) in costs {
+            if let Some(mut attribute) = self.cost_attribute(cost_kind) {
+                attribute.bind_mut().change(-cost_value as f64);
+            }
+        }
+    }
+
+    /// Gives back whatever attacks that missed are owed.
+    fn collect_refunds(&self) {
+        for attack in self.attacks.iter() {
+            let refund = attack.clone().bind_mut().take_refund();
+            for (cost_kind, cost_value) in refund {
+                if let Some(mut attribute) = self.cost_attribute(cost_kind) {
+                    attribute.bind_mut().change(cost_value as f64);
+                }
+            }
         }
     }
 
-    fn handle_attack(&mut self, attack_input: AttackInput) {
-        let attack_node = self
+    fn parse_combos(&self) -> Vec<(Vec<AttackType>, StringName)> {
+        let mut sequences = Vec::new();
+        for combo in self.combos.iter_shared() {
+            let combo = combo.bind();
+            let sequence: Result<Vec<AttackType>, _> = combo
+                .get_sequence()
+                .as_slice()
+                .iter()
+                .map(|name| AttackType::from_str(&name.to_string()))
+                .collect();
+            match sequence {
+                Ok(sequence) if !sequence.is_empty() => {
+                    sequences.push((sequence, combo.get_finisher().clone()))
+                }
+                _ => godot_print!(
+                    "Invalid combo sequence for finisher '{}'",
+                    combo.get_finisher()
+                ),
+            }
+        }
+        sort_combos(&mut sequences);
+        sequences
+    }
+
+    fn on_player_took_damage(&mut self, _damage: i64) {
+        self.reset_combo();
+    }
+
+    fn on_player_died(&mut self) {
+        self.queued_attack = None;
+        self.charging = None;
+        self.reset_combo();
+        if let Some(mut active) = self.active_attack() {
+            active.bind_mut().cancel();
+        }
+        // Stops input handling and auto attacks for good.
+        let mut base = self.base_mut();
+        base.set_process(false);
+        base.set_process_input(false);
+    }
+
+    fn reset_combo(&mut self) {
+        self.combo_history.clear();
+        self.combo_timer = 0.0;
+    }
+
+    /// The finisher to fire instead of `attack_type`'s attack if it would complete a combo.
+    /// Nothing is recorded until the attack has actually started.
+    fn combo_finisher(&self, attack_type: AttackType) -> Option<Gd<Attack>> {
+        let mut history = self.combo_history.clone();
+        history.push(attack_type);
+        let finisher = matching_combo(&self.combo_sequences, &history)?.clone();
+        let attack = self
             .attacks
-            .get(attack_input.attack.to_node_index())
-            // Gd<Node2D> is a ref counted pointer, so we can clone it for ~free
+            .iter()
+            .find(|attack| attack.bind().get_attack_name() == finisher)
             .cloned();
-        if let Some(mut attack_node) = attack_node {
-            let (cost_kind, cost_value) = attack_node.bind().get_cost();
-            if self.can_afford_attack(cost_kind, cost_value) {
-                self.base_mut()
-                    .set_rotation_degrees(attack_input.attack_direction.to_rotation());
-                attack_node.bind_mut().start();
+        if attack.is_none() {
+            godot_print!("Combo finisher '{}' not found!", finisher);
+        }
+        attack
+    }
+
+    /// Records an input whose attack has started, resetting the combo if it fired a finisher.
+    fn register_combo_input(&mut self, attack_type: AttackType, fired_finisher: bool) {
+        if fired_finisher {
+            self.reset_combo();
+            return;
+        }
+        self.combo_history.push(attack_type);
+        self.combo_timer = 0.0;
+        trim_combo_history(&mut self.combo_history, &self.combo_sequences);
+    }
+
+    /// Buffers an input, replacing any older buffered input.
+    fn queue_attack(&mut self, attack_input: AttackInput) {
+        self.queued_attack = Some(QueuedAttack {
+            input: attack_input,
+            age: 0.0,
+        });
+    }
+
+    /// The manual attack currently playing, auto attacks run independently.
+    fn active_attack(&self) -> Option<Gd<Attack>> {
+        self.attacks
+            .iter()
+            .find(|attack| {
+                let attack = attack.bind();
+                attack.is_busy() && !attack.is_auto()
+            })
+            .cloned()
+    }
+
+    /// Starts the buffered attack once nothing else is playing, or straight away if it may
+    /// cancel the current one.
+    ///
+    /// This polls rather than reacting to `attack_finished` so that an attack can be queued
+    /// behind itself without re-entering its own signal handler.
+    fn dispatch_queued_attack(&mut self) {
+        let Some(queued) = self.queued_attack.take() else {
+            return;
+        };
+        // Resolved now rather than when buffered, so the combo reflects attacks that started.
+        let finisher = self.combo_finisher(queued.input.attack);
+        let Some(attack_node) = finisher
+            .clone()
+            .or_else(|| self.get_attack(queued.input.attack))
+        else {
+            return;
+        };
+        if let Some(mut active) = self.active_attack() {
+            if !attack_node.bind().can_cancel() {
+                self.queued_attack = Some(queued);
+                return;
             }
+            active.bind_mut().cancel();
+        }
+        if self.handle_attack(attack_node, queued.input) {
+            self.register_combo_input(queued.input.attack, finisher.is_some());
+        }
+    }
+
+    /// Pays for and starts an attack, returning whether it started.
+    fn handle_attack(&mut self, mut attack_node: Gd<Attack>, attack_input: AttackInput) -> bool {
+        if !self.pay_for_attack(&attack_node, attack_input.charge) {
+            return false;
+        }
+        // Rotate the attack itself, not the manager, so auto attacks mid-swing stay on target.
+        let rotation = attack_node.bind().aim_rotation(attack_input.aim);
+        attack_node.set_rotation_degrees(rotation);
+        attack_node.bind_mut().start()
+    }
+
+    /// Checks an attack can start and spends its cost, returning whether it may go ahead.
+    fn pay_for_attack(&self, attack_node: &Gd<Attack>, charge: f32) -> bool {
+        // Check readiness first so a busy or cooling down attack doesn't spend its cost.
+        if !attack_node.bind().can_start() {
+            return false;
+        }
+        let costs = attack_node.bind().get_costs(charge);
+        if !self.can_afford(&costs) {
+            return false;
         }
+        self.spend(&costs);
+        let mut attack_node = attack_node.clone();
+        let mut attack = attack_node.bind_mut();
+        attack.set_charge(charge);
+        attack.set_spent_costs(costs);
+        true
+    }
+
+    /// Fires any auto attacks whose timers have elapsed at an enemy in range.
+    fn fire_auto_attacks(&mut self) {
+        let due: Vec<Gd<Attack>> = self
+            .attacks
+            .iter()
+            .filter(|attack| attack.bind().is_auto_fire_due())
+            .cloned()
+            .collect();
+        if due.is_empty() {
+            return;
+        }
+        let Some(tree) = self.base().get_tree() else {
+            return;
+        };
+        let origin = self.base().get_global_position();
+        let offsets: Vec<Vector2> = living_enemies(&tree)
+            .iter()
+            .map(|enemy| enemy.get_global_position() - origin)
+            .collect();
+
+        for mut attack in due {
+            let (trigger, range) = {
+                let attack = attack.bind();
+                (attack.get_trigger(), attack.get_auto_range())
+            };
+            let in_range: Vec<Vector2> = offsets
+                .iter()
+                .copied()
+                .filter(|offset| offset.length() <= range)
+                .collect();
+            let target = match trigger {
+                AttackTrigger::Manual => None,
+                AttackTrigger::AutoNearest => in_range
+                    .iter()
+                    .copied()
+                    .min_by(|a, b| a.length().total_cmp(&b.length())),
+                AttackTrigger::AutoRandom if !in_range.is_empty() => {
+                    let index = self.rng.randi_range(0, in_range.len() as i32 - 1);
+                    Some(in_range[index as usize])
+                }
+                AttackTrigger::AutoRandom => None,
+            };
+            let Some(target) = target else {
+                continue;
+            };
+
+            if self.pay_for_attack(&attack, 0.0) {
+                // Like manual attacks, auto attacks aim themselves rather than the manager.
+                let rotation = attack.bind().aim_rotation(target.normalized_or_zero());
+                attack.set_global_rotation_degrees(rotation);
+                attack.bind_mut().start();
+            }
+        }
+    }
+}
+
+/// Orders combos longest first, so the longest matching combo is preferred.
+fn sort_combos<T>(sequences: &mut [(Vec<AttackType>, T)]) {
+    sequences.sort_by_key(|(sequence, _)| std::cmp::Reverse(sequence.len()));
+}
+
+/// The finisher of the first combo, in `sort_combos` order, that `history` ends with.
+fn matching_combo<'a, T>(
+    sequences: &'a [(Vec<AttackType>, T)],
+    history: &[AttackType],
+) -> Option<&'a T> {
+    sequences
+        .iter()
+        .find(|(sequence, _)| history.ends_with(sequence))
+        .map(|(_, finisher)| finisher)
+}
+
+/// Drops inputs too old to be part of any combo in `sequences`, sorted by `sort_combos`.
+fn trim_combo_history<T>(history: &mut Vec<AttackType>, sequences: &[(Vec<AttackType>, T)]) {
+    let longest = sequences.first().map_or(0, |(sequence, _)| sequence.len());
+    if history.len() > longest {
+        let excess = history.len() - longest;
+        history.drain(..excess);
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
-enum AttackType {
+#[derive(
+    GodotConvert, Var, Export, Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, EnumString,
+)]
+#[godot(via = GString)]
+pub enum AttackType {
     Main,
     Secondary,
     Slot1,
@@ -124,22 +693,90 @@ impl AttackType {
             AttackType::Slot4 => "attack_slot_4",
         }
     }
+}
 
-    /// Index of an array of children that represent attacks
-    fn to_node_index(&self) -> usize {
-        match self {
-            AttackType::Main => 0,
-            AttackType::Secondary => 1,
-            AttackType::Slot1 => 2,
-            AttackType::Slot2 => 3,
-            AttackType::Slot3 => 4,
-            AttackType::Slot4 => 5,
-        }
-    }
+#[derive(Debug, Clone, Copy)]
+struct AttackInput {
+    attack: AttackType,
+    /// Normalised direction the player aimed in, snapped per attack by its `AimMode`.
+    aim: Vector2,
+    /// Charge progress (0-1) for chargeable attacks.
+    charge: f32,
+}
+
+const GAMEPAD_DEVICE_DEADZONE: f32 = 0.5;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum AimDevice {
+    #[default]
+    Mouse,
+    Gamepad,
+}
+
+struct QueuedAttack {
+    input: AttackInput,
+    /// Seconds the input has been buffered for.
+    age: f64,
 }
 
 #[derive(Debug, Clone)]
-struct AttackInput {
+struct ChargeState {
     attack: AttackType,
-    attack_direction: Facing8,
+    /// Seconds the input has been held for.
+    held: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use AttackType::{Main, Secondary, Slot1};
+
+    fn combos() -> Vec<(Vec<AttackType>, &'static str)> {
+        let mut combos = vec![
+            (vec![Main, Secondary], "short"),
+            (vec![Main, Main, Secondary], "long"),
+            (vec![Slot1], "single"),
+        ];
+        sort_combos(&mut combos);
+        combos
+    }
+
+    #[test]
+    fn prefers_the_longest_matching_combo() {
+        assert_eq!(
+            matching_combo(&combos(), &[Main, Main, Secondary]),
+            Some(&"long")
+        );
+        assert_eq!(
+            matching_combo(&combos(), &[Secondary, Main, Secondary]),
+            Some(&"short")
+        );
+    }
+
+    #[test]
+    fn only_matches_the_end_of_the_history() {
+        assert_eq!(matching_combo(&combos(), &[Main, Secondary, Main]), None);
+        assert_eq!(matching_combo(&combos(), &[]), None);
+    }
+
+    #[test]
+    fn trims_history_to_the_longest_combo() {
+        let mut history = vec![Slot1, Secondary, Main, Main, Secondary];
+        trim_combo_history(&mut history, &combos());
+        assert_eq!(history, vec![Main, Main, Secondary]);
+    }
+
+    #[test]
+    fn keeps_short_history_untouched() {
+        let mut history = vec![Main];
+        trim_combo_history(&mut history, &combos());
+        assert_eq!(history, vec![Main]);
+    }
+
+    #[test]
+    fn clears_history_without_combos() {
+        let mut history = vec![Main, Secondary];
+        trim_combo_history::<&str>(&mut history, &[]);
+        assert!(history.is_empty());
+    }
 }
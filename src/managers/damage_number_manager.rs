@@ -0,0 +1,157 @@
+use std::collections::VecDeque;
+
+use godot::{
+    classes::{Label, SceneTree},
+    prelude::*,
+};
+
+/// Scene tree group the damage number manager adds itself to, so `Health` can find it.
+pub const DAMAGE_NUMBERS_GROUP: &str = "damage_numbers";
+
+#[derive(GodotConvert, Var, Export, Debug, Clone, Copy, PartialEq, Eq)]
+#[godot(via = i32)]
+pub enum DamageNumberStyle {
+    Normal,
+    Crit,
+    Heal,
+    Resisted,
+}
+
+#[derive(GodotClass)]
+#[class(base=Node2D)]
+pub struct DamageNumberManager {
+    base: Base<Node2D>,
+    /// Labels created up front when the manager is ready.
+    #[export]
+    pool_size: i32,
+    /// Once this many labels are showing, the oldest is reused for the next number.
+    #[export]
+    max_labels: i32,
+    #[export]
+    lifetime: f64,
+    /// Pixels per second numbers rise by.
+    #[export]
+    rise_speed: f32,
+    #[export]
+    normal_color: Color,
+    #[export]
+    crit_color: Color,
+    #[export]
+    heal_color: Color,
+    #[export]
+    resisted_color: Color,
+    #[export]
+    crit_scale: f32,
+    free_labels: Vec<Gd<Label>>,
+    active_labels: VecDeque<ActiveLabel>,
+}
+
+struct ActiveLabel {
+    label: Gd<Label>,
+    age: f64,
+}
+
+#[godot_api]
+impl INode2D for DamageNumberManager {
+    fn init(base: Base<Node2D>) -> Self {
+        Self {
+            base,
+            pool_size: 64,
+            max_labels: 256,
+            lifetime: 0.8,
+            rise_speed: 40.0,
+            normal_color: Color::WHITE,
+            crit_color: Color::from_rgb(1.0, 0.85, 0.0),
+            heal_color: Color::from_rgb(0.3, 1.0, 0.3),
+            resisted_color: Color::from_rgb(0.6, 0.6, 0.6),
+            crit_scale: 1.5,
+            free_labels: Vec::new(),
+            active_labels: VecDeque::new(),
+        }
+    }
+
+    fn enter_tree(&mut self) {
+        // Joined here rather than in ready so it's findable before any Health is ready.
+        self.base_mut().add_to_group(DAMAGE_NUMBERS_GROUP);
+    }
+
+    fn ready(&mut self) {
+        godot_print!("Damage number manager ready!");
+        for _ in 0..self.pool_size {
+            let label = self.create_label();
+            self.free_labels.push(label);
+        }
+    }
+
+    fn process(&mut self, delta: f64) {
+        let rise = Vector2::new(0.0, -self.rise_speed * delta as f32);
+        for active in self.active_labels.iter_mut() {
+            active.age += delta;
+            let position = active.label.get_position();
+            active.label.set_position(position + rise);
+            let alpha = (1.0 - active.age / self.lifetime).clamp(0.0, 1.0) as f32;
+            active
+                .label
+                .set_modulate(Color::from_rgba(1.0, 1.0, 1.0, alpha));
+        }
+
+        // Labels are spawned in order, so the oldest are always at the front.
+        while self
+            .active_labels
+            .front()
+            .is_some_and(|active| active.age >= self.lifetime)
+        {
+            if let Some(mut active) = self.active_labels.pop_front() {
+                active.label.set_visible(false);
+                self.free_labels.push(active.label);
+            }
+        }
+    }
+}
+
+#[godot_api]
+impl DamageNumberManager {
+    #[func]
+    pub fn spawn(&mut self, global_position: Vector2, amount: i64, style: DamageNumberStyle) {
+        let pooled = self.free_labels.pop();
+        let mut label = match pooled {
+            Some(label) => label,
+            None if (self.active_labels.len() as i32) < self.max_labels => self.create_label(),
+            None => match self.active_labels.pop_front() {
+                Some(oldest) => oldest.label,
+                None => return,
+            },
+        };
+
+        let (text, color, scale) = match style {
+            DamageNumberStyle::Normal => (amount.to_string(), self.normal_color, 1.0),
+            DamageNumberStyle::Crit => (format!("{}!", amount), self.crit_color, self.crit_scale),
+            DamageNumberStyle::Heal => (format!("+{}", amount), self.heal_color, 1.0),
+            DamageNumberStyle::Resisted => (amount.to_string(), self.resisted_color, 1.0),
+        };
+        label.set_text(text.as_str());
+        label.add_theme_color_override("font_color", color);
+        label.set_scale(Vector2::new(scale, scale));
+        label.set_modulate(Color::WHITE);
+        label.set_global_position(global_position);
+        label.set_visible(true);
+
+        self.active_labels
+            .push_back(ActiveLabel { label, age: 0.0 });
+    }
+}
+
+impl DamageNumberManager {
+    fn create_label(&mut self) -> Gd<Label> {
+        let mut label = Label::new_alloc();
+        label.set_visible(false);
+        self.base_mut().add_child(&label);
+        label
+    }
+
+    /// Finds the damage number manager in `tree`, if the level has one.
+    pub fn find(tree: &Gd<SceneTree>) -> Option<Gd<DamageNumberManager>> {
+        tree.get_first_node_in_group(DAMAGE_NUMBERS_GROUP)
+            .and_then(|node| node.try_cast::<DamageNumberManager>().ok())
+    }
+}
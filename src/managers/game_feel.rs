@@ -0,0 +1,139 @@
+use godot::{
+    classes::{Engine, SceneTree, Time},
+    global::randf_range,
+    prelude::*,
+};
+
+/// Scene tree group the game feel service adds itself to, so attacks can find it.
+pub const GAME_FEEL_GROUP: &str = "game_feel";
+
+/// Hit-stop and camera shake that attacks can request when they land.
+#[derive(GodotClass)]
+#[class(base=Node)]
+pub struct GameFeel {
+    base: Base<Node>,
+    /// Time scale while a hit-stop is active.
+    #[export]
+    hit_stop_time_scale: f64,
+    /// Longest a single hit-stop can last, in real seconds.
+    #[export]
+    max_hit_stop: f64,
+    /// Real seconds after a hit-stop ends before another may start.
+    #[export]
+    hit_stop_cooldown: f64,
+    /// Largest camera offset, in pixels, a shake can reach.
+    #[export]
+    max_shake: f32,
+    /// Pixels of shake lost per real second.
+    #[export]
+    shake_decay: f32,
+    restore_time_scale: f64,
+    hit_stop_until_usec: Option<u64>,
+    next_hit_stop_usec: u64,
+    shake: f32,
+    last_process_usec: u64,
+}
+
+#[godot_api]
+impl INode for GameFeel {
+    fn init(base: Base<Node>) -> Self {
+        Self {
+            base,
+            hit_stop_time_scale: 0.05,
+            max_hit_stop: 0.15,
+            hit_stop_cooldown: 0.1,
+            max_shake: 12.0,
+            shake_decay: 40.0,
+            restore_time_scale: 1.0,
+            hit_stop_until_usec: None,
+            next_hit_stop_usec: 0,
+            shake: 0.0,
+            last_process_usec: 0,
+        }
+    }
+
+    fn enter_tree(&mut self) {
+        self.base_mut().add_to_group(GAME_FEEL_GROUP);
+    }
+
+    fn exit_tree(&mut self) {
+        // Don't leave the game slowed down if the level is unloaded mid hit-stop.
+        if self.hit_stop_until_usec.take().is_some() {
+            Engine::singleton().set_time_scale(self.restore_time_scale);
+        }
+    }
+
+    fn ready(&mut self) {
+        godot_print!("Game feel service ready!");
+        self.last_process_usec = Time::singleton().get_ticks_usec();
+    }
+
+    fn process(&mut self, _delta: f64) {
+        // Hit-stop scales delta, so measure real time instead.
+        let now = Time::singleton().get_ticks_usec();
+        let real_delta = (now - self.last_process_usec) as f32 / 1_000_000.0;
+        self.last_process_usec = now;
+
+        if let Some(until) = self.hit_stop_until_usec {
+            if now >= until {
+                Engine::singleton().set_time_scale(self.restore_time_scale);
+                self.hit_stop_until_usec = None;
+                self.next_hit_stop_usec = now + seconds_to_usec(self.hit_stop_cooldown);
+            }
+        }
+
+        if self.shake > 0.0 {
+            // Reaches exactly zero on the last frame, which re-centres the camera.
+            self.shake = (self.shake - self.shake_decay * real_delta).max(0.0);
+            let offset = Vector2::new(randf_range(-1.0, 1.0) as f32, randf_range(-1.0, 1.0) as f32)
+                * self.shake;
+            self.set_camera_offset(offset);
+        }
+    }
+}
+
+#[godot_api]
+impl GameFeel {
+    /// Briefly slows time. Requests made during, or just after, another hit-stop are ignored
+    /// so area attacks hitting many bodies don't chain into a long freeze.
+    #[func]
+    pub fn request_hit_stop(&mut self, duration: f64) {
+        let now = Time::singleton().get_ticks_usec();
+        if duration <= 0.0 || self.hit_stop_until_usec.is_some() || now < self.next_hit_stop_usec {
+            return;
+        }
+        let mut engine = Engine::singleton();
+        self.restore_time_scale = engine.get_time_scale();
+        engine.set_time_scale(self.hit_stop_time_scale);
+        self.hit_stop_until_usec = Some(now + seconds_to_usec(duration.min(self.max_hit_stop)));
+    }
+
+    /// Shakes the camera by up to `strength` pixels. Overlapping requests take the strongest
+    /// rather than adding up.
+    #[func]
+    pub fn request_shake(&mut self, strength: f32) {
+        self.shake = self.shake.max(strength).min(self.max_shake);
+    }
+}
+
+impl GameFeel {
+    /// Finds the game feel service in `tree`, if the level has one.
+    pub fn find(tree: &Gd<SceneTree>) -> Option<Gd<GameFeel>> {
+        tree.get_first_node_in_group(GAME_FEEL_GROUP)
+            .and_then(|node| node.try_cast::<GameFeel>().ok())
+    }
+
+    fn set_camera_offset(&self, offset: Vector2) {
+        let camera = self
+            .base()
+            .get_viewport()
+            .and_then(|viewport| viewport.get_camera_2d());
+        if let Some(mut camera) = camera {
+            camera.set_offset(offset);
+        }
+    }
+}
+
+fn seconds_to_usec(seconds: f64) -> u64 {
+    (seconds.max(0.0) * 1_000_000.0) as u64
+}
@@ -1,2 +1,5 @@
 mod attack_manager;
+pub mod damage_number_manager;
 mod enemy_manager;
+pub mod game_feel;
+pub mod run_stats;
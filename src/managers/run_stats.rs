@@ -0,0 +1,112 @@
+use godot::{classes::SceneTree, prelude::*};
+
+use crate::components::{team_of, Team};
+
+/// Scene tree group the run stats tracker adds itself to, so gameplay code can report to it.
+pub const RUN_STATS_GROUP: &str = "run_stats";
+
+/// Statistics for the current run, shown on the game over screen.
+#[derive(GodotClass)]
+#[class(base=Node)]
+pub struct RunStats {
+    base: Base<Node>,
+    #[var]
+    time_survived: f64,
+    #[var]
+    kills: i64,
+    #[var]
+    damage_dealt: i64,
+    #[var]
+    damage_taken: i64,
+    finished: bool,
+}
+
+#[godot_api]
+impl INode for RunStats {
+    fn init(base: Base<Node>) -> Self {
+        Self {
+            base,
+            time_survived: 0.0,
+            kills: 0,
+            damage_dealt: 0,
+            damage_taken: 0,
+            finished: false,
+        }
+    }
+
+    fn enter_tree(&mut self) {
+        self.base_mut().add_to_group(RUN_STATS_GROUP);
+    }
+
+    fn ready(&mut self) {
+        godot_print!("Run stats ready!");
+    }
+
+    fn process(&mut self, delta: f64) {
+        if !self.finished {
+            self.time_survived += delta;
+        }
+    }
+}
+
+#[godot_api]
+impl RunStats {
+    #[func]
+    pub fn record_kill(&mut self) {
+        if !self.finished {
+            self.kills += 1;
+        }
+    }
+
+    #[func]
+    pub fn record_damage_dealt(&mut self, damage: i64) {
+        if !self.finished {
+            self.damage_dealt += damage;
+        }
+    }
+
+    #[func]
+    pub fn record_damage_taken(&mut self, damage: i64) {
+        if !self.finished {
+            self.damage_taken += damage;
+        }
+    }
+
+    /// Stops the clock and ignores anything reported afterwards, e.g. once the player has died.
+    #[func]
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    /// Multi-line summary for the results screen.
+    pub fn summary(&self) -> String {
+        let seconds = self.time_survived as i64;
+        format!(
+            "Time survived: {}:{:02}\nKills: {}\nDamage dealt: {}\nDamage taken: {}",
+            seconds / 60,
+            seconds % 60,
+            self.kills,
+            self.damage_dealt,
+            self.damage_taken
+        )
+    }
+}
+
+impl RunStats {
+    /// Finds the run stats tracker in `tree`, if the level has one.
+    pub fn find(tree: &Gd<SceneTree>) -> Option<Gd<RunStats>> {
+        tree.get_first_node_in_group(RUN_STATS_GROUP)
+            .and_then(|node| node.try_cast::<RunStats>().ok())
+    }
+}
+
+/// Records `damage` dealt by `source` if it is on the player's team, so enemies hurting each
+/// other don't count towards the run.
+pub fn record_player_damage(tree: &Gd<SceneTree>, source: &Gd<Node>, damage: i64) {
+    if team_of(source) != Some(Team::Player) {
+        return;
+    }
+    if let Some(mut stats) = RunStats::find(tree) {
+        stats.bind_mut().record_damage_dealt(damage);
+    }
+}
@@ -1,15 +1,21 @@
 use godot::classes::{
-    AnimatedSprite2D, AnimationTree, CharacterBody2D, CollisionShape2D, ICharacterBody2D, Input,
+    AnimatedSprite2D, AnimationPlayer, AnimationTree, CharacterBody2D, CollisionShape2D,
+    ICharacterBody2D, Input,
 };
 use godot::prelude::*;
 
+use crate::components::generic_attribute::GenericAttribute;
+use crate::components::StatusEffects;
 use crate::core::Facing8;
+use crate::damage::{HitInfo, HitReceiver};
+use crate::managers::run_stats::RunStats;
 
-const MOVEMENT_BLEND_PROPS: [&str; 4] = [
+const MOVEMENT_BLEND_PROPS: [&str; 5] = [
     "parameters/MainSM/Walking/blend_position",
     "parameters/MainSM/Running/blend_position",
     "parameters/MainSM/Sitting Down/blend_position",
     "parameters/MainSM/Idle/blend_position",
+    "parameters/MainSM/Dash/blend_position",
 ];
 
 #[derive(GodotClass)]
@@ -19,13 +25,44 @@ pub struct Player {
     facing: Facing8,
     sprite: OnReady<Gd<AnimatedSprite2D>>,
     animation_tree: OnReady<Gd<AnimationTree>>,
+    status_effects: Option<Gd<StatusEffects>>,
     #[export]
     movement_state: MovementState,
     #[export]
     debug: bool,
-    // TODO jheiser: Move health and stamina to a component.
-    health: i32,
-    stamina: i32,
+    alive: bool,
+    /// Seconds after taking damage during which further hits are ignored.
+    #[export]
+    invulnerability_duration: f64,
+    /// Seconds between blinks while invulnerable.
+    #[export]
+    flash_interval: f64,
+    #[export]
+    hurt_color: Color,
+    invulnerable_remaining: f64,
+    flash_color: Color,
+    hurt_animation_player: Option<Gd<AnimationPlayer>>,
+    #[export]
+    dash_speed: f32,
+    /// Seconds a dash lasts, during which the player is also invulnerable.
+    #[export]
+    dash_duration: f64,
+    /// Seconds from the start of one dash until the next may start.
+    #[export]
+    dash_cooldown: f64,
+    #[export]
+    dash_stamina_cost: f64,
+    /// Stops colliding with `enemy_collision_layer` while dashing.
+    #[export]
+    dash_through_enemies: bool,
+    /// Physics layer number, from 1 to 32, that enemies are on.
+    #[export]
+    enemy_collision_layer: i32,
+    /// The enemy layer's mask value from before the current dash cleared it.
+    enemy_mask_before_dash: Option<bool>,
+    dash_remaining: f64,
+    dash_cooldown_remaining: f64,
+    dash_direction: Vector2,
 }
 
 #[derive(GodotConvert, Var, Export, Default, Debug, Clone, Copy)]
@@ -35,6 +72,7 @@ pub enum MovementState {
     Idle,
     Walking,
     Running,
+    Dash,
 }
 
 #[godot_api]
@@ -45,16 +83,46 @@ impl ICharacterBody2D for Player {
             facing: Facing8::default(),
             sprite: OnReady::from_node("AnimatedSprite2D"),
             animation_tree: OnReady::from_node("AnimationTree"),
+            status_effects: None,
             // TODO jheiser: Consider mirroring attack state from attack manager?
             // attack_state: AttackState::default(),
             movement_state: MovementState::default(),
             debug: false,
-            health: 100,
-            stamina: 200,
+            alive: true,
+            invulnerability_duration: 0.8,
+            flash_interval: 0.1,
+            hurt_color: Color::RED,
+            invulnerable_remaining: 0.0,
+            flash_color: Color::WHITE,
+            hurt_animation_player: None,
+            dash_speed: 450.0,
+            dash_duration: 0.2,
+            dash_cooldown: 0.6,
+            dash_stamina_cost: 25.0,
+            dash_through_enemies: false,
+            enemy_collision_layer: 2,
+            enemy_mask_before_dash: None,
+            dash_remaining: 0.0,
+            dash_cooldown_remaining: 0.0,
+            dash_direction: Vector2::ZERO,
         }
     }
 
     fn ready(&mut self) {
+        self.status_effects = self
+            .base()
+            .try_get_node_as::<StatusEffects>("StatusEffects");
+        self.hurt_animation_player = self
+            .base()
+            .try_get_node_as::<AnimationPlayer>("HurtAnimationPlayer");
+        if self.dash_through_enemies && !(1..=32).contains(&self.enemy_collision_layer) {
+            godot_print!(
+                "Enemy collision layer {} is not between 1 and 32, dashing through enemies disabled!",
+                self.enemy_collision_layer
+            );
+            self.dash_through_enemies = false;
+        }
+
         // Init Debug Label
         let label = self
             .base()
@@ -65,7 +133,12 @@ impl ICharacterBody2D for Player {
         }
     }
 
-    fn process(&mut self, _delta: f64) {
+    fn process(&mut self, delta: f64) {
+        if self.invulnerable_remaining > 0.0 {
+            self.invulnerable_remaining -= delta;
+            self.update_flash();
+        }
+
         let label = self
             .base()
             .try_get_node_as::<godot::classes::Label>("DebugLabel");
@@ -74,10 +147,33 @@ impl ICharacterBody2D for Player {
         }
     }
 
-    fn physics_process(&mut self, _delta: f64) {
+    fn physics_process(&mut self, delta: f64) {
+        if !self.alive {
+            return;
+        }
+        self.dash_cooldown_remaining = (self.dash_cooldown_remaining - delta).max(0.0);
+        if self.dash_remaining > 0.0 {
+            self.dash_remaining -= delta;
+            if self.dash_remaining <= 0.0 {
+                self.end_dash();
+            }
+        }
+
         let input = self.handle_input();
+        if input.dash_pressed {
+            self.try_dash(input.velocity);
+        }
 
-        self.movement_state = if input.velocity != Vector2::ZERO {
+        // A dash keeps going in the direction it started, whatever is held.
+        let direction = if self.dash_remaining > 0.0 {
+            self.dash_direction
+        } else {
+            input.velocity
+        };
+
+        self.movement_state = if self.dash_remaining > 0.0 {
+            MovementState::Dash
+        } else if input.velocity != Vector2::ZERO {
             if input.run_held {
                 MovementState::Running
             } else {
@@ -87,16 +183,20 @@ impl ICharacterBody2D for Player {
             MovementState::Idle
         };
 
+        let speed_multiplier = self.status_effects.as_ref().map_or(1.0, |status_effects| {
+            status_effects.bind().speed_multiplier()
+        });
         let speed = match self.movement_state {
             MovementState::Idle => 0.0,
             MovementState::Walking => 100.0,
             MovementState::Running => 200.0,
-        };
+            MovementState::Dash => self.dash_speed,
+        } * speed_multiplier;
 
-        self.update_facing(input.velocity);
+        self.update_facing(direction);
         // TODO: Update collision shape based on facing direction.
 
-        if let Some(velocity) = input.velocity.try_normalized() {
+        if let Some(velocity) = direction.try_normalized() {
             // Don't update facing if velocity is zero.
             for property in MOVEMENT_BLEND_PROPS {
                 self.animation_tree.set(property, &Variant::from(velocity));
@@ -104,22 +204,180 @@ impl ICharacterBody2D for Player {
         }
 
         let mut base = self.base_mut();
-        base.set_velocity(input.velocity.normalized_or_zero() * speed);
+        base.set_velocity(direction.normalized_or_zero() * speed);
         base.move_and_slide();
     }
 }
 
 #[godot_api]
 impl Player {
-    pub fn get_health(&self) -> i32 {
-        self.health
+    #[signal]
+    pub fn took_damage(damage: i64);
+
+    #[signal]
+    pub fn player_died();
+
+    pub fn get_facing(&self) -> Facing8 {
+        self.facing
+    }
+
+    #[func]
+    pub fn is_alive(&self) -> bool {
+        self.alive
+    }
+
+    #[func]
+    pub fn is_invulnerable(&self) -> bool {
+        self.invulnerable_remaining > 0.0
+    }
+
+    /// Ignores hits for `duration` seconds, e.g. while dodging. Never shortens existing i-frames.
+    #[func]
+    pub fn grant_invulnerability(&mut self, duration: f64) {
+        self.start_invulnerability(duration, Color::WHITE);
+    }
+}
+
+#[godot_dyn]
+impl HitReceiver for Player {
+    fn receive_hit(&mut self, hit: &HitInfo) -> i64 {
+        if !self.alive || (self.is_invulnerable() && !hit.is_tick) {
+            return 0;
+        }
+        let Some(mut health) = self.get_attribute("Health") else {
+            godot_print!("Health node not found in player.");
+            return 0;
+        };
+        let remaining = {
+            let mut health = health.bind_mut();
+            health.change(-(hit.damage as f64));
+            health.get_value()
+        };
+        if let Some(mut stats) = self.run_stats() {
+            stats.bind_mut().record_damage_taken(hit.damage);
+        }
+        self.signals().took_damage().emit(hit.damage);
+        if let (Some(effect), Some(status_effects)) = (&hit.status_effect, &mut self.status_effects)
+        {
+            status_effects.bind_mut().apply(effect);
+        }
+
+        if remaining <= 0.0 {
+            self.die();
+            return hit.damage;
+        }
+
+        // Otherwise a poisoned player would spend most of their time invulnerable.
+        if hit.is_tick {
+            return hit.damage;
+        }
+        self.start_invulnerability(self.invulnerability_duration, self.hurt_color);
+        if let Some(mut animation_player) = self.hurt_animation_player.clone() {
+            animation_player.set_current_animation("hurt");
+            animation_player.play();
+        }
+        hit.damage
     }
-    pub fn get_stamina(&self) -> i32 {
-        self.stamina
+
+    fn current_health(&self) -> f64 {
+        self.get_attribute("Health")
+            .map_or(0.0, |health| health.bind().get_value())
     }
 }
 
 impl Player {
+    pub fn get_attribute(&self, attribute_name: &str) -> Option<Gd<GenericAttribute>> {
+        self.base()
+            .find_child(attribute_name)
+            .and_then(|n| n.try_cast::<GenericAttribute>().ok())
+    }
+
+    fn start_invulnerability(&mut self, duration: f64, color: Color) {
+        if duration <= self.invulnerable_remaining {
+            return;
+        }
+        self.invulnerable_remaining = duration;
+        self.flash_color = color;
+        self.update_flash();
+    }
+
+    /// Blinks the sprite in `flash_color` while invulnerable, restoring it once i-frames end.
+    fn update_flash(&mut self) {
+        let color = if self.invulnerable_remaining <= 0.0 {
+            Color::WHITE
+        } else if self.flash_interval > 0.0
+            && (self.invulnerable_remaining / self.flash_interval) as i64 % 2 == 1
+        {
+            Color {
+                a: 0.3,
+                ..self.flash_color
+            }
+        } else {
+            self.flash_color
+        };
+        self.sprite.set_modulate(color);
+    }
+
+    /// Starts a dash along `input_velocity`, or the facing direction if nothing is held.
+    fn try_dash(&mut self, input_velocity: Vector2) {
+        if self.dash_remaining > 0.0 || self.dash_cooldown_remaining > 0.0 {
+            return;
+        }
+        if self.dash_stamina_cost > 0.0 {
+            let Some(mut stamina) = self.get_attribute("Stamina") else {
+                godot_print!("Stamina node not found in player.");
+                return;
+            };
+            if !stamina.bind_mut().take(self.dash_stamina_cost) {
+                return;
+            }
+        }
+
+        self.dash_direction = input_velocity
+            .try_normalized()
+            .unwrap_or_else(|| self.facing.to_vector());
+        self.dash_remaining = self.dash_duration;
+        self.dash_cooldown_remaining = self.dash_cooldown;
+        self.grant_invulnerability(self.dash_duration);
+        if self.dash_through_enemies {
+            let layer = self.enemy_collision_layer;
+            self.enemy_mask_before_dash = Some(self.base().get_collision_mask_value(layer));
+            self.base_mut().set_collision_mask_value(layer, false);
+        }
+    }
+
+    fn end_dash(&mut self) {
+        self.dash_remaining = 0.0;
+        if let Some(mask) = self.enemy_mask_before_dash.take() {
+            let layer = self.enemy_collision_layer;
+            self.base_mut().set_collision_mask_value(layer, mask);
+        }
+    }
+
+    fn die(&mut self) {
+        self.alive = false;
+        if self.dash_remaining > 0.0 {
+            self.end_dash();
+        }
+        self.invulnerable_remaining = 0.0;
+        self.update_flash();
+        self.movement_state = MovementState::Idle;
+        self.base_mut().set_velocity(Vector2::ZERO);
+        self.animation_tree
+            .set("parameters/MainSM/conditions/death", &Variant::from(true));
+        godot_print!("Player died!");
+        if let Some(mut stats) = self.run_stats() {
+            stats.bind_mut().finish();
+        }
+        self.signals().player_died().emit();
+    }
+
+    fn run_stats(&self) -> Option<Gd<RunStats>> {
+        self.base()
+            .get_tree()
+            .and_then(|tree| RunStats::find(&tree))
+    }
+
     fn handle_input(&mut self) -> InputResult {
         let mut velocity = Vector2::ZERO;
         let input = Input::singleton();
@@ -137,7 +395,8 @@ impl Player {
         }
 
         let run_held = input.is_action_pressed("player_run");
-        InputResult::new(velocity, run_held)
+        let dash_pressed = input.is_action_just_pressed("player_dash");
+        InputResult::new(velocity, run_held, dash_pressed)
     }
     fn update_facing(&mut self, velocity: Vector2) {
         if velocity != Vector2::ZERO {
@@ -155,9 +414,14 @@ impl Player {
 struct InputResult {
     velocity: Vector2,
     run_held: bool,
+    dash_pressed: bool,
 }
 impl InputResult {
-    fn new(velocity: Vector2, run_held: bool) -> Self {
-        Self { velocity, run_held }
+    fn new(velocity: Vector2, run_held: bool, dash_pressed: bool) -> Self {
+        Self {
+            velocity,
+            run_held,
+            dash_pressed,
+        }
     }
 }
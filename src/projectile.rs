@@ -0,0 +1,102 @@
+use std::collections::HashSet;
+
+use godot::{
+    classes::{Area2D, IArea2D},
+    prelude::*,
+};
+
+use crate::{attack::Attack, damage::is_hit_receiver};
+
+#[derive(GodotClass)]
+#[class(base=Area2D)]
+pub struct Projectile {
+    base: Base<Area2D>,
+    source: Option<Gd<Attack>>,
+    direction: Vector2,
+    speed: f32,
+    lifetime: f64,
+    pierce_remaining: i32,
+    age: f64,
+    seen_bodies: HashSet<InstanceId>,
+}
+
+#[godot_api]
+impl IArea2D for Projectile {
+    fn init(base: Base<Area2D>) -> Self {
+        Self {
+            base,
+            source: None,
+            direction: Vector2::UP,
+            speed: 0.0,
+            lifetime: 0.0,
+            pierce_remaining: 0,
+            age: 0.0,
+            seen_bodies: HashSet::new(),
+        }
+    }
+
+    fn ready(&mut self) {
+        self.signals()
+            .body_entered()
+            .connect_self(Self::on_body_entered);
+    }
+
+    fn physics_process(&mut self, delta: f64) {
+        self.age += delta;
+        if self.age >= self.lifetime {
+            self.base_mut().queue_free();
+            return;
+        }
+
+        let motion = self.direction * self.speed * (delta as f32);
+        let position = self.base().get_global_position();
+        self.base_mut().set_global_position(position + motion);
+    }
+}
+
+#[godot_api]
+impl Projectile {
+    fn on_body_entered(&mut self, body: Gd<Node2D>) {
+        if !self.seen_bodies.insert(body.instance_id()) {
+            return;
+        }
+
+        // Walls and other level geometry stop projectiles outright.
+        if !is_hit_receiver(&body) {
+            self.base_mut().queue_free();
+            return;
+        }
+
+        // The attack that fired us may have been freed (e.g. swapped out) mid-flight.
+        let Some(mut source) = self.source.clone().filter(|s| s.is_instance_valid()) else {
+            self.base_mut().queue_free();
+            return;
+        };
+
+        if source.bind_mut().apply_hit(body, self.direction) {
+            if self.pierce_remaining <= 0 {
+                self.base_mut().queue_free();
+            } else {
+                self.pierce_remaining -= 1;
+            }
+        }
+    }
+}
+
+impl Projectile {
+    /// Must be called before the projectile is added to the tree.
+    pub fn launch(
+        &mut self,
+        source: Gd<Attack>,
+        direction: Vector2,
+        speed: f32,
+        lifetime: f64,
+        pierce: i32,
+    ) {
+        self.source = Some(source);
+        self.direction = direction.normalized_or_zero();
+        self.speed = speed;
+        self.lifetime = lifetime;
+        self.pierce_remaining = pierce;
+    }
+}